@@ -1,6 +1,6 @@
 #![allow(dead_code)]
 use std::{env, error::Error};
-use twilio_async::{MsgResp, Twilio, TwilioJson, TwilioRequest};
+use twilio_async::{MsgResp, Twilio, TwilioRequest};
 
 type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync + 'static>>;
 
@@ -17,10 +17,8 @@ async fn main() -> Result<()> {
 async fn try_conference(twilio: Twilio) -> Result<()> {
     let _resp = twilio.conferences().run().await?;
 
-    let resp = twilio
-        .conference("EH5bc4f5c62684f43d0acadb3d88a43e38")
-        .run()
-        .await?;
+    let sid = "CF5bc4f5c62684f43d0acadb3d88a43e38".parse()?;
+    let resp = twilio.conference(&sid).run().await?;
 
     println!("{:?}", resp);
     Ok(())
@@ -39,15 +37,12 @@ async fn try_msg(twilio: Twilio) -> Result<()> {
         .run()
         .await?;
     // get individual msg
-    if let TwilioJson::Success(MsgResp { sid, .. }) = resp {
-        let resp = twilio.msg(&sid).run().await?;
-        println!("{:?}", resp);
-    }
+    let MsgResp { sid, .. } = resp;
+    let resp = twilio.msg(&sid).run().await?;
+    println!("{:?}", resp);
     // delete a message
-    twilio
-        .msg("SM5585720d3f244b1cb054862040b7b858")
-        .delete()
-        .await?;
+    let sid = "SM5585720d3f244b1cb054862040b7b858".parse()?;
+    twilio.msg(&sid).delete().await?;
     // there's also redact()
     // get a msg media url
     // twilio.msg("messagesid").media()?;
@@ -1,12 +1,21 @@
-use super::{encode_pairs, Execute, Twilio, TwilioErr, TwilioJson, TwilioRequest, TwilioResp};
+use super::{
+    encode_pairs, AccountSid, Address, CallSid, DateTime, Execute, Page, Paging, PhoneNumberSid,
+    TimeFilter, Twilio, TwilioErr, TwilioRequest, TwilioResp,
+};
+use crate::phone::{self, IntoAddress, ParsePhoneError};
+use crate::twiml::{self, Twiml};
+#[cfg(feature = "rust_decimal")]
+use crate::Price;
 use async_trait::async_trait;
 use hyper::{self, Method};
 use serde::Deserialize;
+use std::fmt;
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct Call<'a> {
-    from: &'a str,
-    to: &'a str,
+    from: Address,
+    to: Address,
+    invalid: Option<ParsePhoneError>,
     url: &'a str,
     sid: Option<&'a str>,
     callerid: Option<&'a str>,
@@ -29,19 +38,33 @@ pub enum CallbackEvent {
 use self::CallbackEvent::*;
 
 impl<'a> Call<'a> {
-    pub fn new(from: &'a str, to: &'a str, url: &'a str) -> Call<'a> {
+    pub fn new(from: impl IntoAddress, to: impl IntoAddress, url: &'a str) -> Call<'a> {
+        let mut invalid = None;
         Call {
-            from,
-            to,
+            from: phone::checked(from, &mut invalid),
+            to: phone::checked(to, &mut invalid),
+            invalid,
             url,
-            ..Call::default()
+            sid: None,
+            callerid: None,
+            machine_detection: None,
+            record: None,
+            send_digits: None,
+            status_callback: None,
+            callback_event: None,
+            timeout: None,
         }
     }
 }
 
-impl<'a> ToString for Call<'a> {
-    fn to_string(&self) -> String {
-        let mut pairs = vec![("To", self.to), ("From", self.from), ("Url", self.url)];
+impl<'a> fmt::Display for Call<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (to, from) = (self.to.to_string(), self.from.to_string());
+        let mut pairs = vec![
+            ("To", to.as_str()),
+            ("From", from.as_str()),
+            ("Url", self.url),
+        ];
         pair!(self, sid, "ApplicationSid", pairs);
         pair!(self, callerid, "CallerId", pairs);
         if let Some(detection) = self.machine_detection {
@@ -67,11 +90,11 @@ impl<'a> ToString for Call<'a> {
         pair!(self, send_digits, "SendDigits", pairs);
         pair!(self, status_callback, "StatusCallback", pairs);
 
-        encode_pairs(pairs).unwrap()
+        f.write_str(&encode_pairs(pairs).unwrap())
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 #[allow(non_camel_case_types)]
 pub enum CallStatus {
     queued,
@@ -86,26 +109,50 @@ pub enum CallStatus {
     noanswer,
 }
 
+impl CallStatus {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match *self {
+            CallStatus::queued => "queued",
+            CallStatus::ringing => "ringing",
+            CallStatus::inprogress => "in-progress",
+            CallStatus::canceled => "canceled",
+            CallStatus::completed => "completed",
+            CallStatus::failed => "failed",
+            CallStatus::busy => "busy",
+            CallStatus::noanswer => "no-answer",
+        }
+    }
+}
+
 #[derive(Deserialize, Debug)]
 pub struct CallResp {
     pub from: String,
     pub to: String,
-    pub sid: String,
-    pub start_time: Option<String>,
+    pub sid: CallSid,
+    #[serde(default, deserialize_with = "crate::datetime::deserialize_opt")]
+    pub start_time: Option<DateTime>,
     pub status: CallStatus,
-    pub account_sid: String,
+    pub account_sid: AccountSid,
     pub caller_name: Option<String>,
     pub duration: Option<i32>,
+    #[cfg(not(feature = "rust_decimal"))]
     pub price: Option<String>,
+    #[cfg(not(feature = "rust_decimal"))]
     pub price_unit: String,
+    #[cfg(feature = "rust_decimal")]
+    #[serde(flatten, deserialize_with = "crate::price::deserialize")]
+    pub price: Option<Price>,
     pub uri: String,
-    pub date_created: Option<String>,
-    pub end_time: Option<String>,
-    pub phone_number_sid: String,
+    #[serde(default, deserialize_with = "crate::datetime::deserialize_opt")]
+    pub date_created: Option<DateTime>,
+    #[serde(default, deserialize_with = "crate::datetime::deserialize_opt")]
+    pub end_time: Option<DateTime>,
+    /// `None` for calls to or from a `client:` or `sip:` address
+    pub phone_number_sid: Option<PhoneNumberSid>,
     pub direction: Direction,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 #[allow(non_camel_case_types)]
 pub enum Direction {
     inbound,
@@ -122,22 +169,35 @@ pub enum Direction {
 #[derive(Debug)]
 pub struct SendCall<'a> {
     pub call: Call<'a>,
+    pub idempotency_token: Option<&'a str>,
     pub client: &'a Twilio,
 }
 
-execute!(SendCall);
+execute!(SendCall, idempotency_token);
 
 #[async_trait]
 impl<'a> TwilioRequest for SendCall<'a> {
     type Resp = CallResp;
 
-    async fn run(&self) -> TwilioResp<TwilioJson<Self::Resp>> {
+    async fn run(&self) -> TwilioResp<Self::Resp> {
+        if let Some(ref e) = self.call.invalid {
+            return Err(TwilioErr::PhoneErr(e.clone()));
+        }
         let call = self.call.to_string();
         self.execute(Method::POST, "Calls.json", Some(call)).await
     }
 }
 
 impl<'a> SendCall<'a> {
+    /// Lets the retry policy resend the call after a 429 or 5xx, not only
+    /// when the connection failed. The token is sent as
+    /// `I-Twilio-Idempotency-Token`, but Twilio does not deduplicate on it, so
+    /// only set it when a duplicate call is acceptable
+    pub fn idempotency_token(mut self, token: &'a str) -> SendCall<'a> {
+        self.idempotency_token = Some(token);
+        self
+    }
+
     pub fn sid(mut self, sid: &'a str) -> SendCall<'a> {
         self.call.sid = Some(sid);
         self
@@ -178,3 +238,235 @@ impl<'a> SendCall<'a> {
         self
     }
 }
+
+// GET ONE CALL
+#[derive(Debug)]
+pub struct GetCall<'a> {
+    pub call_sid: &'a CallSid,
+    pub client: &'a Twilio,
+}
+
+execute!(GetCall);
+
+#[async_trait]
+impl<'a> TwilioRequest for GetCall<'a> {
+    type Resp = CallResp;
+
+    async fn run(&self) -> TwilioResp<Self::Resp> {
+        let url = format!("Calls/{}.json", self.call_sid);
+        self.execute(Method::GET, url, None).await
+    }
+}
+
+impl<'a> GetCall<'a> {
+    async fn update(&self, pairs: &[(&str, &str)]) -> TwilioResp<CallResp> {
+        let url = format!("Calls/{}.json", self.call_sid);
+        self.execute(Method::POST, url, encode_pairs(pairs)).await
+    }
+
+    /// Fetch new TwiML for the in-progress call from `url`
+    pub async fn redirect(&self, url: &str, method: twiml::Method) -> TwilioResp<CallResp> {
+        let method = match method {
+            twiml::Method::Get => "GET",
+            twiml::Method::Post => "POST",
+        };
+        self.update(&[("Url", url), ("Method", method)]).await
+    }
+
+    /// Replace whatever the call is doing with `twiml`
+    pub async fn twiml<T: Twiml>(&self, twiml: &T) -> TwilioResp<CallResp> {
+        let twiml = twiml.build()?;
+        self.update(&[("Twiml", &twiml)]).await
+    }
+
+    /// End a call that is still `queued` or `ringing`
+    pub async fn cancel(&self) -> TwilioResp<CallResp> {
+        self.update(&[("Status", "canceled")]).await
+    }
+
+    /// Hang up a call that is `in-progress`
+    pub async fn complete(&self) -> TwilioResp<CallResp> {
+        self.update(&[("Status", "completed")]).await
+    }
+
+    pub async fn delete(&self) -> TwilioResp<()> {
+        let url = format!("Calls/{}.json", self.call_sid);
+        self.execute(Method::DELETE, url, None).await
+    }
+}
+
+// GET ALL CALLS
+#[derive(Debug)]
+pub struct Calls<'a> {
+    pub client: &'a Twilio,
+    pub to: Option<&'a str>,
+    pub from: Option<&'a str>,
+    pub parent_call_sid: Option<&'a CallSid>,
+    pub status: Option<CallStatus>,
+    pub start_time: TimeFilter<'a>,
+    pub end_time: TimeFilter<'a>,
+    pub paging: Paging,
+}
+
+impl<'a> Calls<'a> {
+    pub fn to(mut self, to: &'a str) -> Calls<'a> {
+        self.to = Some(to);
+        self
+    }
+
+    pub fn from(mut self, from: &'a str) -> Calls<'a> {
+        self.from = Some(from);
+        self
+    }
+
+    pub fn parent_call_sid(mut self, sid: &'a CallSid) -> Calls<'a> {
+        self.parent_call_sid = Some(sid);
+        self
+    }
+
+    pub fn status(mut self, status: CallStatus) -> Calls<'a> {
+        self.status = Some(status);
+        self
+    }
+
+    /// Only calls that started within `filter`, dates are `YYYY-MM-DD`
+    pub fn start_time(mut self, filter: TimeFilter<'a>) -> Calls<'a> {
+        self.start_time = filter;
+        self
+    }
+
+    /// Only calls that ended within `filter`, dates are `YYYY-MM-DD`
+    pub fn end_time(mut self, filter: TimeFilter<'a>) -> Calls<'a> {
+        self.end_time = filter;
+        self
+    }
+
+    fn list_url(&self) -> String {
+        let mut pairs = Vec::new();
+        pair!(self, to, "To", pairs);
+        pair!(self, from, "From", pairs);
+        if let Some(sid) = self.parent_call_sid {
+            pairs.push(("ParentCallSid", sid.as_str()));
+        }
+        if let Some(status) = self.status {
+            pairs.push(("Status", status.as_str()));
+        }
+        self.start_time
+            .pairs(["StartTime", "StartTime<", "StartTime>"], &mut pairs);
+        self.end_time
+            .pairs(["EndTime", "EndTime<", "EndTime>"], &mut pairs);
+        match encode_pairs(pairs) {
+            Some(query) if !query.is_empty() => format!("Calls.json?{}", query),
+            _ => "Calls.json".into(),
+        }
+    }
+}
+
+execute!(Calls);
+paginate!(Calls, ListCallsResp);
+
+#[async_trait]
+impl<'a> TwilioRequest for Calls<'a> {
+    type Resp = ListCallsResp;
+
+    async fn run(&self) -> TwilioResp<Self::Resp> {
+        let url = self.paging.url(self.list_url());
+        self.execute(Method::GET, url, None).await
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ListCallsResp {
+    pub calls: Vec<CallResp>,
+    pub page: usize,
+    pub page_size: usize,
+    pub start: usize,
+    pub end: usize,
+    pub uri: String,
+    pub next_page_uri: Option<String>,
+    pub previous_page_uri: Option<String>,
+}
+
+impl Page for ListCallsResp {
+    type Item = CallResp;
+
+    fn next_page_uri(&self) -> Option<&str> {
+        self.next_page_uri.as_deref()
+    }
+
+    fn into_items(self) -> Vec<CallResp> {
+        self.calls
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CallStatus;
+    use crate::transport::Canned;
+    use crate::twiml::{self, Response};
+    use crate::TimeFilter;
+    use crate::Twilio;
+
+    const CALL: &str = r#"{"from": "+15005550006", "to": "+14155550100",
+        "sid": "CA00000000000000000000000000000001", "start_time": null, "status": "canceled",
+        "account_sid": "AC00000000000000000000000000000001", "caller_name": null, "duration": null,
+        "price": null, "price_unit": "USD", "uri": "/uri", "date_created": null, "end_time": null,
+        "phone_number_sid": null, "direction": "outbound-api"}"#;
+
+    #[tokio::test]
+    async fn updates_call() {
+        let canned = Canned::new(200, CALL);
+        let twilio = canned.client();
+        let sid = "CA00000000000000000000000000000001".parse().unwrap();
+        let call = twilio.call_sid(&sid);
+        call.redirect("https://example.com/next", twiml::Method::Post)
+            .await
+            .unwrap();
+        call.twiml(&Response::new().hangup()).await.unwrap();
+        call.cancel().await.unwrap();
+        call.complete().await.unwrap();
+
+        let url = "https://api.twilio.com/2010-04-01/Accounts/AC1/Calls/\
+                   CA00000000000000000000000000000001.json";
+        assert_eq!(canned.seen(), vec![format!("POST {}", url); 4]);
+        assert_eq!(
+            canned.bodies(),
+            [
+                "Url=https%3A%2F%2Fexample.com%2Fnext&Method=POST",
+                "Twiml=%3CResponse%3E%3CHangup+%2F%3E%3C%2FResponse%3E",
+                "Status=canceled",
+                "Status=completed",
+            ]
+        );
+
+        let canned = Canned::new(204, "");
+        let twilio = canned.client();
+        twilio.call_sid(&sid).delete().await.unwrap();
+        assert_eq!(canned.seen(), [format!("DELETE {}", url)]);
+    }
+
+    #[test]
+    fn encodes_list_filters() {
+        let client = Twilio::new("AC123", "token").unwrap();
+        let calls = client
+            .calls()
+            .status(CallStatus::inprogress)
+            .start_time(TimeFilter::between("2020-01-01", "2020-01-31"))
+            .end_time(TimeFilter::on("2020-02-01"))
+            .page_size(50);
+        assert_eq!(
+            calls.paging.url(calls.list_url()),
+            "Calls.json?Status=in-progress&StartTime%3E=2020-01-01\
+             &StartTime%3C=2020-01-31&EndTime=2020-02-01&PageSize=50"
+        );
+        let calls = client
+            .calls()
+            .start_time(TimeFilter::before("2020-01-01"))
+            .end_time(TimeFilter::after("2020-01-02"));
+        assert_eq!(
+            calls.list_url(),
+            "Calls.json?StartTime%3C=2020-01-01&EndTime%3E=2020-01-02"
+        );
+        assert_eq!(client.calls().list_url(), "Calls.json");
+    }
+}
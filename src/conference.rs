@@ -1,20 +1,26 @@
-use super::{encode_pairs, Execute, Twilio, TwilioErr, TwilioJson, TwilioRequest, TwilioResp};
+use super::{
+    encode_pairs, AccountSid, CallSid, ConferenceSid, DateTime, Execute, Page, Paging, TimeFilter,
+    Twilio, TwilioErr, TwilioRequest, TwilioResp,
+};
 use async_trait::async_trait;
 use hyper::{self, Method};
 use serde::Deserialize;
+use std::collections::HashMap;
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct Conference<'a> {
-    sid: &'a str,
-    status: Option<&'a str>,
+    sid: &'a ConferenceSid,
+    status: Option<ConferenceStatus>,
 }
 
-const COMPLETED: &str = "completed";
-
 impl<'a> Conference<'a> {
-    pub fn new(sid: &'a str) -> Conference<'a> {
+    pub fn new(sid: &'a ConferenceSid) -> Conference<'a> {
         Conference { sid, status: None }
     }
+
+    pub(crate) fn sid(&self) -> &'a ConferenceSid {
+        self.sid
+    }
 }
 
 // GET ONE CONFERENCE
@@ -26,7 +32,7 @@ pub struct GetConference<'a> {
 
 impl<'a> GetConference<'a> {
     pub fn end(mut self) -> GetConference<'a> {
-        self.conference.status = Some(COMPLETED);
+        self.conference.status = Some(ConferenceStatus::completed);
         self
     }
 }
@@ -37,14 +43,14 @@ execute!(GetConference);
 impl<'a> TwilioRequest for GetConference<'a> {
     type Resp = ConferenceResp;
 
-    async fn run(&self) -> TwilioResp<TwilioJson<Self::Resp>> {
+    async fn run(&self) -> TwilioResp<Self::Resp> {
         let url = format!("Conferences/{}.json", self.conference.sid);
         match self.conference.status {
             Some(status) => {
                 self.execute(
                     Method::POST,
                     url,
-                    Some(encode_pairs(&[("Status", status)]).unwrap()),
+                    Some(encode_pairs(&[("Status", status.as_str())]).unwrap()),
                 )
                 .await
             }
@@ -57,16 +63,63 @@ impl<'a> TwilioRequest for GetConference<'a> {
 #[derive(Debug)]
 pub struct Conferences<'a> {
     pub client: &'a Twilio,
+    pub friendly_name: Option<&'a str>,
+    pub status: Option<ConferenceStatus>,
+    pub date_created: TimeFilter<'a>,
+    pub date_updated: TimeFilter<'a>,
+    pub paging: Paging,
+}
+
+impl<'a> Conferences<'a> {
+    pub fn friendly_name(mut self, friendly_name: &'a str) -> Conferences<'a> {
+        self.friendly_name = Some(friendly_name);
+        self
+    }
+
+    pub fn status(mut self, status: ConferenceStatus) -> Conferences<'a> {
+        self.status = Some(status);
+        self
+    }
+
+    /// Only conferences created within `filter`, dates are `YYYY-MM-DD`
+    pub fn date_created(mut self, filter: TimeFilter<'a>) -> Conferences<'a> {
+        self.date_created = filter;
+        self
+    }
+
+    /// Only conferences last updated within `filter`, dates are `YYYY-MM-DD`
+    pub fn date_updated(mut self, filter: TimeFilter<'a>) -> Conferences<'a> {
+        self.date_updated = filter;
+        self
+    }
+
+    fn list_url(&self) -> String {
+        let mut pairs = Vec::new();
+        pair!(self, friendly_name, "FriendlyName", pairs);
+        if let Some(status) = self.status {
+            pairs.push(("Status", status.as_str()));
+        }
+        self.date_created
+            .pairs(["DateCreated", "DateCreated<", "DateCreated>"], &mut pairs);
+        self.date_updated
+            .pairs(["DateUpdated", "DateUpdated<", "DateUpdated>"], &mut pairs);
+        match encode_pairs(pairs) {
+            Some(query) if !query.is_empty() => format!("Conferences.json?{}", query),
+            _ => "Conferences.json".into(),
+        }
+    }
 }
 
 execute!(Conferences);
+paginate!(Conferences, ListConferencesResp);
 
 #[async_trait]
 impl<'a> TwilioRequest for Conferences<'a> {
     type Resp = ListConferencesResp;
 
-    async fn run(&self) -> TwilioResp<TwilioJson<Self::Resp>> {
-        self.execute(Method::GET, "Conferences.json", None).await
+    async fn run(&self) -> TwilioResp<Self::Resp> {
+        let url = self.paging.url(self.list_url());
+        self.execute(Method::GET, url, None).await
     }
 }
 
@@ -82,14 +135,121 @@ pub struct ListConferencesResp {
     pub page_size: usize,
 }
 
+impl Page for ListConferencesResp {
+    type Item = ConferenceResp;
+
+    fn next_page_uri(&self) -> Option<&str> {
+        self.next_page_uri.as_deref()
+    }
+
+    fn into_items(self) -> Vec<ConferenceResp> {
+        self.conferences
+    }
+}
+
 #[derive(Deserialize, Debug)]
 pub struct ConferenceResp {
-    pub account_sid: String,
-    pub date_created: Option<String>,
-    pub date_updated: String,
+    pub account_sid: AccountSid,
+    #[serde(default, deserialize_with = "crate::datetime::deserialize_opt")]
+    pub date_created: Option<DateTime>,
+    #[serde(deserialize_with = "crate::datetime::deserialize")]
+    pub date_updated: DateTime,
     pub friendly_name: String,
     pub region: String,
-    pub sid: String,
-    pub status: String,
+    pub sid: ConferenceSid,
+    pub status: ConferenceStatus,
     pub uri: String,
+    pub api_version: Option<String>,
+    /// why the conference ended, e.g. `last-participant-left`
+    pub reason_conference_ended: Option<String>,
+    /// the participant that ended the conference by leaving
+    pub call_sid_ending_conference: Option<CallSid>,
+    /// `participants` and `recordings` uris
+    #[serde(default)]
+    pub subresource_uris: HashMap<String, String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub enum ConferenceStatus {
+    init,
+    #[serde(rename = "in-progress")]
+    inprogress,
+    completed,
+}
+
+impl ConferenceStatus {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match *self {
+            ConferenceStatus::init => "init",
+            ConferenceStatus::inprogress => "in-progress",
+            ConferenceStatus::completed => "completed",
+        }
+    }
+}
+
+/// `StatusCallbackEvent` of a conference status callback
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConferenceEvent {
+    ConferenceStart,
+    ConferenceEnd,
+    ParticipantJoin,
+    ParticipantLeave,
+    ParticipantMute,
+    ParticipantUnmute,
+    ParticipantHold,
+    ParticipantUnhold,
+    ParticipantModify,
+    ParticipantSpeechStart,
+    ParticipantSpeechStop,
+    AnnouncementEnd,
+    AnnouncementFail,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConferenceResp, ConferenceStatus};
+    use crate::{TimeFilter, Twilio};
+
+    #[test]
+    fn encodes_list_filters() {
+        let client = Twilio::new("AC123", "token").unwrap();
+        let confs = client
+            .conferences()
+            .friendly_name("room 1")
+            .status(ConferenceStatus::completed)
+            .date_created(TimeFilter::after("2020-01-01"))
+            .date_updated(TimeFilter::on("2020-01-31"));
+        assert_eq!(
+            confs.list_url(),
+            "Conferences.json?FriendlyName=room+1&Status=completed\
+             &DateCreated%3E=2020-01-01&DateUpdated=2020-01-31"
+        );
+        assert_eq!(client.conferences().list_url(), "Conferences.json");
+    }
+
+    #[test]
+    fn parses_end_reason() {
+        let conf: ConferenceResp = serde_json::from_str(
+            r#"{"account_sid": "AC00000000000000000000000000000001", "date_created": null,
+            "date_updated": "Tue, 31 Aug 2010 20:36:28 +0000", "friendly_name": "room",
+            "region": "us1", "sid": "CF00000000000000000000000000000001", "status": "completed",
+            "uri": "/uri", "api_version": "2010-04-01",
+            "reason_conference_ended": "participant-with-end-conference-on-exit-left",
+            "call_sid_ending_conference": "CA00000000000000000000000000000001",
+            "subresource_uris": {"participants": "/participants.json"}}"#,
+        )
+        .unwrap();
+        assert_eq!(conf.api_version.as_deref(), Some("2010-04-01"));
+        assert_eq!(
+            conf.reason_conference_ended.as_deref(),
+            Some("participant-with-end-conference-on-exit-left")
+        );
+        assert_eq!(
+            conf.call_sid_ending_conference.unwrap().as_str(),
+            "CA00000000000000000000000000000001"
+        );
+        assert_eq!(conf.subresource_uris["participants"], "/participants.json");
+    }
 }
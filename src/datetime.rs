@@ -0,0 +1,232 @@
+//! Timestamps in responses and dates in list filters.
+//!
+//! Twilio sends timestamps as RFC 2822 strings (`Tue, 31 Aug 2010 20:36:28
+//! +0000`). [`DateTime`] keeps that string, and also holds it parsed as a
+//! `chrono::DateTime<Utc>` with the `chrono` feature and as a
+//! `time::OffsetDateTime` with the `time` feature, so turning either feature
+//! on never changes the type other crates see.
+use serde::{de, Deserialize, Deserializer};
+use std::{borrow::Cow, fmt};
+
+/// A timestamp from a response
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DateTime {
+    raw: String,
+    #[cfg(feature = "chrono")]
+    chrono: chrono::DateTime<chrono::Utc>,
+    #[cfg(feature = "time")]
+    time: time::OffsetDateTime,
+}
+
+impl DateTime {
+    /// the RFC 2822 string Twilio sent
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn chrono(&self) -> chrono::DateTime<chrono::Utc> {
+        self.chrono
+    }
+
+    #[cfg(feature = "time")]
+    pub fn time(&self) -> time::OffsetDateTime {
+        self.time
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<DateTime> for chrono::DateTime<chrono::Utc> {
+    fn from(dt: DateTime) -> chrono::DateTime<chrono::Utc> {
+        dt.chrono
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<DateTime> for time::OffsetDateTime {
+    fn from(dt: DateTime) -> time::OffsetDateTime {
+        dt.time
+    }
+}
+
+fn parse<E: de::Error>(raw: String) -> Result<DateTime, E> {
+    Ok(DateTime {
+        #[cfg(feature = "chrono")]
+        chrono: chrono::DateTime::parse_from_rfc2822(&raw)
+            .map(|dt| dt.with_timezone(&chrono::Utc))
+            .map_err(E::custom)?,
+        #[cfg(feature = "time")]
+        time: time::OffsetDateTime::parse(&raw, &time::format_description::well_known::Rfc2822)
+            .map_err(E::custom)?,
+        raw,
+    })
+}
+
+pub(crate) fn deserialize<'de, D>(de: D) -> Result<DateTime, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    parse(String::deserialize(de)?)
+}
+
+pub(crate) fn deserialize_opt<'de, D>(de: D) -> Result<Option<DateTime>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(de)?.map(parse).transpose()
+}
+
+/// A date to filter a list by, sent as `YYYY-MM-DD`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterDate<'a>(Cow<'a, str>);
+
+impl<'a> FilterDate<'a> {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<'a> fmt::Display for FilterDate<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// taken as is, it should already be `YYYY-MM-DD`
+impl<'a> From<&'a str> for FilterDate<'a> {
+    fn from(date: &'a str) -> FilterDate<'a> {
+        FilterDate(Cow::Borrowed(date))
+    }
+}
+
+impl From<String> for FilterDate<'static> {
+    fn from(date: String) -> FilterDate<'static> {
+        FilterDate(Cow::Owned(date))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveDate> for FilterDate<'static> {
+    fn from(date: chrono::NaiveDate) -> FilterDate<'static> {
+        date.format("%Y-%m-%d").to_string().into()
+    }
+}
+
+/// the date in `Tz`
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> From<chrono::DateTime<Tz>> for FilterDate<'static> {
+    fn from(dt: chrono::DateTime<Tz>) -> FilterDate<'static> {
+        dt.date_naive().into()
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<time::Date> for FilterDate<'static> {
+    fn from(date: time::Date) -> FilterDate<'static> {
+        format!(
+            "{:04}-{:02}-{:02}",
+            date.year(),
+            u8::from(date.month()),
+            date.day()
+        )
+        .into()
+    }
+}
+
+/// the date at the offset of `dt`
+#[cfg(feature = "time")]
+impl From<time::OffsetDateTime> for FilterDate<'static> {
+    fn from(dt: time::OffsetDateTime) -> FilterDate<'static> {
+        dt.date().into()
+    }
+}
+
+/// Which dates a list is filtered to. Each list takes one filter per date
+/// field, so setting it again, e.g. `on` and then `between`, replaces the
+/// earlier filter instead of narrowing it.
+#[derive(Debug, Default, Clone)]
+pub enum TimeFilter<'a> {
+    #[default]
+    Any,
+    On(FilterDate<'a>),
+    Before(FilterDate<'a>),
+    After(FilterDate<'a>),
+    Between(FilterDate<'a>, FilterDate<'a>),
+}
+
+impl<'a> TimeFilter<'a> {
+    pub fn on(date: impl Into<FilterDate<'a>>) -> TimeFilter<'a> {
+        TimeFilter::On(date.into())
+    }
+
+    pub fn before(date: impl Into<FilterDate<'a>>) -> TimeFilter<'a> {
+        TimeFilter::Before(date.into())
+    }
+
+    pub fn after(date: impl Into<FilterDate<'a>>) -> TimeFilter<'a> {
+        TimeFilter::After(date.into())
+    }
+
+    pub fn between(
+        after: impl Into<FilterDate<'a>>,
+        before: impl Into<FilterDate<'a>>,
+    ) -> TimeFilter<'a> {
+        TimeFilter::Between(after.into(), before.into())
+    }
+
+    /// `keys` are the parameter names for on, before and after
+    pub(crate) fn pairs<'b>(
+        &'b self,
+        keys: [&'static str; 3],
+        pairs: &mut Vec<(&'static str, &'b str)>,
+    ) {
+        let [on_key, before_key, after_key] = keys;
+        match self {
+            TimeFilter::Any => {}
+            TimeFilter::On(on) => pairs.push((on_key, on.as_str())),
+            TimeFilter::Before(before) => pairs.push((before_key, before.as_str())),
+            TimeFilter::After(after) => pairs.push((after_key, after.as_str())),
+            TimeFilter::Between(after, before) => {
+                pairs.push((after_key, after.as_str()));
+                pairs.push((before_key, before.as_str()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::value::{Error, StrDeserializer};
+
+    #[test]
+    fn parses_rfc2822() {
+        let de = StrDeserializer::<Error>::new("Tue, 31 Aug 2010 20:36:28 +0000");
+        let dt = deserialize(de).unwrap();
+        assert_eq!(dt.as_str(), "Tue, 31 Aug 2010 20:36:28 +0000");
+        #[cfg(feature = "chrono")]
+        assert_eq!(dt.chrono().to_rfc3339(), "2010-08-31T20:36:28+00:00");
+        #[cfg(feature = "time")]
+        assert_eq!(dt.time().unix_timestamp(), 1283286988);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn formats_chrono_dates() {
+        let date = chrono::NaiveDate::from_ymd_opt(2020, 1, 5).unwrap();
+        assert_eq!(FilterDate::from(date).as_str(), "2020-01-05");
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn formats_time_dates() {
+        let date = time::Date::from_calendar_date(2020, time::Month::January, 5).unwrap();
+        assert_eq!(FilterDate::from(date).as_str(), "2020-01-05");
+    }
+}
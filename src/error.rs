@@ -1,3 +1,5 @@
+use crate::{ParsePhoneError, ParseSidError};
+use serde::Deserialize;
 use std::{cell, error::Error, fmt, io, string};
 
 // Errors
@@ -7,10 +9,18 @@ pub enum TwilioErr {
     UrlParse(http::uri::InvalidUri),
     NetworkErr(hyper::Error),
     SerdeErr(serde_json::Error),
+    FormErr(serde_urlencoded::de::Error),
     BorrowErr(cell::BorrowMutError),
     Utf8Err(string::FromUtf8Error),
     HttpErr(http::Error),
     HeaderErr(typed_headers::Error),
+    TwimlErr(twiml::TwimlErr),
+    /// a string handed in as a SID is not one of the expected kind
+    SidErr(ParseSidError),
+    PhoneErr(ParsePhoneError),
+    Api(ApiError),
+    /// a message was given more than [`MAX_MEDIA`](crate::MAX_MEDIA) media urls
+    TooManyMedia(usize),
 }
 
 pub use super::TwilioErr::*;
@@ -22,12 +32,17 @@ impl Error for TwilioErr {
         match *self {
             Io(ref e) => e.source(),
             SerdeErr(ref e) => e.source(),
+            FormErr(ref e) => e.source(),
             UrlParse(ref e) => e.source(),
             NetworkErr(ref e) => e.source(),
             BorrowErr(ref e) => e.source(),
             Utf8Err(ref e) => e.source(),
             HttpErr(ref e) => e.source(),
             HeaderErr(ref e) => e.source(),
+            TwimlErr(ref e) => e.source(),
+            SidErr(ref e) => e.source(),
+            PhoneErr(ref e) => e.source(),
+            Api(_) | TooManyMedia(_) => None,
         }
     }
 }
@@ -37,21 +52,149 @@ impl fmt::Display for TwilioErr {
         match *self {
             Io(ref e) => write!(f, "IO Error: {}", e),
             SerdeErr(ref e) => write!(f, "Serde JSON Error: {}", e),
+            FormErr(ref e) => write!(f, "Error parsing form body: {}", e),
             UrlParse(ref e) => write!(f, "URL parse error: {}", e),
             NetworkErr(ref e) => write!(f, "There was a network error. {}", e),
             BorrowErr(ref e) => write!(f, "Error trying to get client reference. {}", e),
             Utf8Err(ref e) => write!(f, "Error converting to utf-8 string: {}", e),
             HttpErr(ref e) => write!(f, "Http error when building req: {}", e),
             HeaderErr(ref e) => write!(f, "Error creating header value: {}", e),
+            TwimlErr(ref e) => write!(f, "Error building TwiML: {}", e),
+            SidErr(ref e) => write!(f, "Invalid SID: {}", e),
+            PhoneErr(ref e) => write!(f, "Invalid address: {}", e),
+            Api(ref e) => write!(f, "Twilio API error: {}", e),
+            TooManyMedia(n) => write!(
+                f,
+                "A message takes at most {} media urls, got {}",
+                crate::MAX_MEDIA,
+                n
+            ),
         }
     }
 }
 
 from!(cell::BorrowMutError, BorrowErr);
+from!(ApiError, Api);
 from!(hyper::Error, NetworkErr);
 from!(serde_json::Error, SerdeErr);
+from!(serde_urlencoded::de::Error, FormErr);
 from!(http::uri::InvalidUri, UrlParse);
 from!(io::Error, Io);
 from!(string::FromUtf8Error, Utf8Err);
 from!(http::Error, HttpErr);
 from!(typed_headers::Error, HeaderErr);
+from!(twiml::TwimlErr, TwimlErr);
+from!(ParseSidError, SidErr);
+from!(ParsePhoneError, PhoneErr);
+
+/// Error body Twilio returns alongside any non 2xx status
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApiError {
+    /// Twilio error code, see <https://www.twilio.com/docs/api/errors>
+    pub code: Option<usize>,
+    pub message: String,
+    pub more_info: Option<String>,
+    /// HTTP status of the response
+    #[serde(default)]
+    pub status: u16,
+}
+
+/// Well-known error codes worth handling explicitly
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiErrorKind {
+    /// 20003
+    Authentication,
+    /// 20404
+    NotFound,
+    /// 20429
+    TooManyRequests,
+    /// 21211
+    InvalidToNumber,
+    /// 21212
+    InvalidFromNumber,
+    /// 21408
+    RegionNotEnabled,
+    /// 21606
+    FromNotSmsCapable,
+    /// 21610
+    Unsubscribed,
+    /// 21611
+    QueueOverflow,
+    /// 21614
+    NotMobileNumber,
+    Other,
+}
+
+impl ApiError {
+    pub fn kind(&self) -> ApiErrorKind {
+        match self.code {
+            Some(20003) => ApiErrorKind::Authentication,
+            Some(20404) => ApiErrorKind::NotFound,
+            Some(20429) => ApiErrorKind::TooManyRequests,
+            Some(21211) => ApiErrorKind::InvalidToNumber,
+            Some(21212) => ApiErrorKind::InvalidFromNumber,
+            Some(21408) => ApiErrorKind::RegionNotEnabled,
+            Some(21606) => ApiErrorKind::FromNotSmsCapable,
+            Some(21610) => ApiErrorKind::Unsubscribed,
+            Some(21611) => ApiErrorKind::QueueOverflow,
+            Some(21614) => ApiErrorKind::NotMobileNumber,
+            _ => ApiErrorKind::Other,
+        }
+    }
+
+    /// 21211, the 'To' number is not a valid phone number
+    pub fn is_invalid_to_number(&self) -> bool {
+        self.kind() == ApiErrorKind::InvalidToNumber
+    }
+
+    /// 21610, the recipient replied STOP and can't be messaged
+    pub fn is_unsubscribed(&self) -> bool {
+        self.kind() == ApiErrorKind::Unsubscribed
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.status == 404
+    }
+
+    pub fn is_rate_limited(&self) -> bool {
+        self.status == 429 || self.kind() == ApiErrorKind::TooManyRequests
+    }
+}
+
+impl ApiError {
+    /// Twilio usually sends a JSON error body, when it doesn't (proxies,
+    /// gateways) fall back to the raw body and the status line
+    pub(crate) fn from_response(status: http::StatusCode, body: &[u8]) -> ApiError {
+        match serde_json::from_slice::<ApiError>(body) {
+            Ok(mut err) => {
+                err.status = status.as_u16();
+                err
+            }
+            Err(_) => ApiError {
+                code: None,
+                message: match String::from_utf8_lossy(body).trim() {
+                    "" => status.canonical_reason().unwrap_or_default().to_owned(),
+                    body => body.to_owned(),
+                },
+                more_info: None,
+                status: status.as_u16(),
+            },
+        }
+    }
+}
+
+impl Error for ApiError {}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", self.status)?;
+        if let Some(code) = self.code {
+            write!(f, "(code {}) ", code)?;
+        }
+        write!(f, "{}", self.message)?;
+        if let Some(ref info) = self.more_info {
+            write!(f, ", see {}", info)?;
+        }
+        Ok(())
+    }
+}
@@ -23,7 +23,8 @@
 //! An async and ergonomic wrapper around Twilio API & TwiML.
 //!
 //! All types can run `run()` or a similar function. They return a value that
-//! implements `Deserialize`.
+//! implements `Deserialize`, any non 2xx response from Twilio comes back as
+//! `TwilioErr::Api`.
 //!
 //! The `examples/` dir has up to date working example code.
 //!
@@ -32,32 +33,49 @@
 //! ```rust,no_run
 //!
 //! use std::env;
-//! use twilio_async::{Twilio, TwilioRequest};
+//! use twilio_async::{MessageSid, PhoneNumber, Region, Twilio, TwilioRequest};
 //!
 //! #[tokio::main]
 //! async fn main() -> Result<(), Box<dyn std::error::Error>> {
 //!     let twilio = Twilio::new(env::var("TWILIO_SID")?, env::var("TWILIO_TOKEN")?)?;
 //!     // sending a message
 //!     twilio.send_msg("from", "to", "Hello World").run().await?;
+//!     // check a number and normalize it to E.164 before sending
+//!     let to = PhoneNumber::parse("(415) 555-0100", Region::US)?;
+//!     twilio.send_msg("from", to, "Hello World").run().await?;
+//!     // strings starting with `+`, `client:`, `sip:` or `whatsapp:` are checked too,
+//!     // others are sent as is for short codes and alphanumeric sender ids
 //!     // sending a body-less message with media
 //!     twilio
 //!         .send_msg("from", "to", "body")
 //!         .media("http://i0.kym-cdn.com/photos/images/newsfeed/000/377/946/0b9.jpg")
 //!         .run().await?;
-//!     // get details about a message
-//!     twilio.msg("messagesid").run().await?;
+//!     // get details about a message, SIDs are checked when parsed
+//!     let sid: MessageSid = "SM5585720d3f244b1cb054862040b7b858".parse()?;
+//!     twilio.msg(&sid).run().await?;
 //!     // redact a message
-//!     twilio.msg("messagesid").redact().await?;
+//!     twilio.msg(&sid).redact().await?;
+//!     // schedule a message through a messaging service, then cancel it
+//!     twilio
+//!         .send_msg("from", "to", "See you tomorrow")
+//!         .messaging_service_sid(&"MG9752274e9e519418a7406176694466fa".parse()?)
+//!         .send_at("2030-01-01T09:00:00Z")
+//!         .run().await?;
+//!     twilio.msg(&sid).cancel().await?;
 //!     // get a msg media url
-//!     twilio.msg("messagesid").media().await?;
+//!     twilio.msg(&sid).media().await?;
 //!     // delete a msg
-//!     twilio.msg("messagesid").delete().await?;
+//!     twilio.msg(&sid).delete().await?;
 //!     // get all messages
 //!     twilio.msgs().run().await?;
 //!     // get all messages between some time
 //!     twilio.msgs().between("start date", "end date").run().await?;
 //!     // get all messages on a specific date
 //!     twilio.msgs().on("date").run().await?;
+//!     // filters combine
+//!     twilio.msgs().to("+14155550100").on("2020-01-01").run().await?;
+//!     // follow `next_page_uri` and collect every message on every page
+//!     twilio.msgs().page_size(100).collect_all().await?;
 //!     Ok(())
 //! }
 //! ```
@@ -67,20 +85,118 @@
 //! ```rust,no_run
 //!
 //! # use std::{error::Error, env};
-//! # use twilio_async::{Twilio, TwilioJson, TwilioRequest};
+//! # use twilio_async::{CallStatus, TimeFilter, Twilio, TwilioErr, TwilioRequest};
 //!
 //! # #[tokio::main]
 //! # async fn main() -> Result<(), Box<dyn Error>> {
 //! let twilio = Twilio::new(env::var("TWILIO_SID")?, env::var("TWILIO_TOKEN")?)?;
-//! if let TwilioJson::Success(call) = twilio
+//! match twilio
 //!     .call("from", "to", "http://demo.twilio.com/docs/voice.xml")
-//!     .run().await? {
-//!     // do something with `call`
+//!     .run().await {
+//!     Ok(call) => { /* do something with `call` */ }
+//!     // twilio answered with an error status, `e.code` has the error code
+//!     Err(TwilioErr::Api(e)) if e.is_invalid_to_number() => {}
+//!     Err(e) => return Err(e.into()),
+//! }
+//! // every completed call started in january
+//! let calls = twilio
+//!     .calls()
+//!     .status(CallStatus::completed)
+//!     .start_time(TimeFilter::between("2020-01-01", "2020-01-31"))
+//!     .collect_all()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Modify a call in progress:
+//!
+//! ```rust,no_run
+//! # use std::{error::Error, env};
+//! # use twilio_async::{twiml::{Method, Response}, Twilio, TwilioRequest};
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn Error>> {
+//! # let twilio = Twilio::new(env::var("TWILIO_SID")?, env::var("TWILIO_TOKEN")?)?;
+//! let sid = "CA5ef8732a3c49700934481addd5ce1659".parse()?;
+//! let call = twilio.call_sid(&sid);
+//! // fetch it
+//! call.run().await?;
+//! // send it somewhere else
+//! call.redirect("https://example.com/escalate.xml", Method::Post).await?;
+//! // or push TwiML directly
+//! call.twiml(&Response::new().say("Transferring you now")).await?;
+//! // hang up
+//! call.complete().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Conference participants:
+//!
+//! ```rust,no_run
+//! # use std::{error::Error, env};
+//! # use twilio_async::{Beep, Twilio, TwilioRequest};
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn Error>> {
+//! # let twilio = Twilio::new(env::var("TWILIO_SID")?, env::var("TWILIO_TOKEN")?)?;
+//! let sid = "CFbbe4632a3c49700934481addd5ce1659".parse()?;
+//! let conf = twilio.conference(&sid);
+//! // dial someone in
+//! conf.add_participant("from", "to").beep(Beep::OnEnter).run().await?;
+//! // mute everyone else
+//! for p in conf.participants().collect_all().await? {
+//!     conf.participant(&p.call_sid).mute().await?;
 //! }
 //! # Ok(())
 //! # }
 //! ```
 //!
+//! Recordings:
+//!
+//! ```rust,no_run
+//! # use std::{error::Error, env};
+//! # use twilio_async::{PauseBehavior, RecordingChannels, RecordingFormat, Twilio, TwilioRequest};
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn Error>> {
+//! # let twilio = Twilio::new(env::var("TWILIO_SID")?, env::var("TWILIO_TOKEN")?)?;
+//! let mut file = tokio::fs::File::create("call.wav").await?;
+//! let sid = "RE557ce644e5ab84fa21cc21112e22c485".parse()?;
+//! twilio
+//!     .recording(&sid)
+//!     .download(RecordingFormat::Wav)
+//!     .requested_channels(2)
+//!     .write_to(&mut file)
+//!     .await?;
+//!
+//! // record a live call, pausing while the caller reads out card details
+//! let sid = "CA5ef8732a3c49700934481addd5ce1659".parse()?;
+//! let call = twilio.call_sid(&sid);
+//! let rec = call.start_recording().channels(RecordingChannels::Dual).run().await?;
+//! call.recording(&rec.sid).pause(PauseBehavior::Silence).await?;
+//! call.recording(&rec.sid).resume().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Regional edges, mock servers, proxies & retries:
+//!
+//! ```rust
+//! # use twilio_async::{RetryPolicy, Twilio};
+//! # fn main() -> Result<(), twilio_async::TwilioErr> {
+//! let twilio = Twilio::builder("sid", "token")
+//!     .edge("dublin")
+//!     .region("ie1")
+//!     // back off and retry 429s and 5xx up to 3 times in total
+//!     .retry_policy(RetryPolicy::exponential(3))
+//!     .build()?;
+//! assert_eq!(twilio.base_url(), "https://api.dublin.ie1.twilio.com");
+//!
+//! let mock = Twilio::with_base_url("sid", "token", "http://127.0.0.1:8080")?;
+//! assert_eq!(mock.base_url(), "http://127.0.0.1:8080");
+//! # Ok(())
+//! # }
+//! ```
+//!
 //! Twiml:
 //!
 //! ```rust
@@ -100,27 +216,82 @@
 mod macros;
 mod call;
 mod conference;
+mod datetime;
 pub mod error;
 mod message;
+mod page;
+mod participant;
+mod phone;
+#[cfg(feature = "rust_decimal")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rust_decimal")))]
+mod price;
+mod ratelimit;
 mod recording;
+mod retry;
+#[cfg(feature = "server")]
+#[cfg_attr(docsrs, doc(cfg(feature = "server")))]
+pub mod server;
+mod sid;
+mod signature;
+mod transcription;
+pub mod transport;
 pub mod twiml;
+pub mod webhook;
 
-pub use crate::{call::*, conference::*, error::*, message::*, recording::*};
+pub use crate::{
+    call::*,
+    conference::*,
+    datetime::{DateTime, FilterDate, TimeFilter},
+    error::*,
+    message::*,
+    page::{Page, Paging},
+    participant::*,
+    phone::{Address, IntoAddress, ParsePhoneError, PhoneNumber, Region},
+    ratelimit::{RateLimiter, SenderType},
+    recording::*,
+    retry::RetryPolicy,
+    sid::*,
+    transcription::*,
+    transport::{HyperTransport, Transport},
+};
 
 use async_trait::async_trait;
-use hyper::{client::HttpConnector, Body, Client, Method, Request};
-use hyper_tls::HttpsConnector;
-use serde::Deserialize;
-use std::borrow::Borrow;
+use hyper::{Body, Method, Request};
+use std::{borrow::Borrow, fmt, sync::Arc};
 
+#[cfg(feature = "rust_decimal")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rust_decimal")))]
+pub use price::{Currency, Price};
+pub use signature::{compute_signature, validate_body_signature, validate_signature};
 pub use typed_headers::{Authorization, Credentials};
 pub use url::{form_urlencoded, Url};
 
-#[derive(Debug)]
+const API_PATH: &str = "/2010-04-01/Accounts";
+const DEFAULT_SCHEME: &str = "https";
+const DEFAULT_HOST: &str = "api.twilio.com";
+const DEFAULT_REGION: &str = "us1";
+
 pub struct Twilio {
     sid: String,
     auth: Authorization,
-    client: Client<HttpsConnector<HttpConnector>, hyper::Body>,
+    base: String,
+    transport: Box<dyn Transport>,
+    retry: RetryPolicy,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+// `auth` carries the auth token, keep it out of logs
+impl fmt::Debug for Twilio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Twilio")
+            .field("sid", &self.sid)
+            .field("auth", &"<redacted>")
+            .field("base", &self.base)
+            .field("transport", &self.transport)
+            .field("retry", &self.retry)
+            .field("rate_limiter", &self.rate_limiter)
+            .finish()
+    }
 }
 
 pub type TwilioResp<T> = Result<T, TwilioErr>;
@@ -131,24 +302,66 @@ impl Twilio {
         S: Into<String>,
         P: AsRef<str>,
     {
-        let sid = sid.into();
-        let client = Client::builder().build::<_, hyper::Body>(HttpsConnector::new());
+        Twilio::builder(sid, token).build()
+    }
 
-        Ok(Twilio {
-            auth: Authorization(Credentials::basic(&sid, token.as_ref())?),
-            sid,
-            client,
-        })
+    /// Start configuring a client, use this to talk to a regional edge, a
+    /// local mock server or anything other than `https://api.twilio.com`
+    pub fn builder<S, P>(sid: S, token: P) -> TwilioBuilder
+    where
+        S: Into<String>,
+        P: AsRef<str>,
+    {
+        TwilioBuilder::new(sid, token)
+    }
+
+    /// Shorthand for `Twilio::builder(sid, token).base_url(url).build()`
+    pub fn with_base_url<S, P, U>(sid: S, token: P, url: U) -> TwilioResult<Twilio>
+    where
+        S: Into<String>,
+        P: AsRef<str>,
+        U: Into<String>,
+    {
+        Twilio::builder(sid, token).base_url(url).build()
+    }
+
+    /// The scheme and host (including edge and region) requests are sent to,
+    /// e.g. `https://api.dublin.ie1.twilio.com`
+    pub fn base_url(&self) -> &str {
+        &self.base
+    }
+
+    pub(crate) fn url<U: AsRef<str>>(&self, url: U) -> String {
+        let url = url.as_ref();
+        // uris handed back by twilio, like `next_page_uri`, are already
+        // relative to the host
+        if url.starts_with('/') {
+            format!("{}{}", self.base, url)
+        } else {
+            format!("{}{}/{}/{}", self.base, API_PATH, self.sid, url)
+        }
     }
 
-    pub fn send_msg<'a>(&'a self, from: &'a str, to: &'a str, body: &'a str) -> SendMsg<'a> {
+    /// `from` and `to` can be an `Address`, a `PhoneNumber` or a string.
+    /// Strings starting with `+`, `client:`, `sip:` or `whatsapp:` are
+    /// checked and `run()` fails with `TwilioErr::PhoneErr` if they are
+    /// malformed; any other string is sent unchecked as a short code or
+    /// alphanumeric sender id.
+    pub fn send_msg<'a>(
+        &'a self,
+        from: impl IntoAddress,
+        to: impl IntoAddress,
+        body: &'a str,
+    ) -> SendMsg<'a> {
         SendMsg {
             msg: Msg::new(from, to, body),
+            idempotency_token: None,
+            rate_limiter: None,
             client: self,
         }
     }
 
-    pub fn msg<'a>(&'a self, message_sid: &'a str) -> GetMessage<'a> {
+    pub fn msg<'a>(&'a self, message_sid: &'a MessageSid) -> GetMessage<'a> {
         GetMessage {
             message_sid,
             client: self,
@@ -156,17 +369,50 @@ impl Twilio {
     }
 
     pub fn msgs(&self) -> Messages<'_> {
-        Messages { client: self }
+        Messages {
+            client: self,
+            to: None,
+            from: None,
+            date_sent: TimeFilter::Any,
+            paging: Paging::default(),
+        }
     }
 
-    pub fn call<'a>(&'a self, from: &'a str, to: &'a str, url: &'a str) -> SendCall<'a> {
+    /// `from` and `to` are checked like in `send_msg`
+    pub fn call<'a>(
+        &'a self,
+        from: impl IntoAddress,
+        to: impl IntoAddress,
+        url: &'a str,
+    ) -> SendCall<'a> {
         SendCall {
             call: Call::new(from, to, url),
+            idempotency_token: None,
+            client: self,
+        }
+    }
+
+    pub fn call_sid<'a>(&'a self, call_sid: &'a CallSid) -> GetCall<'a> {
+        GetCall {
+            call_sid,
+            client: self,
+        }
+    }
+
+    pub fn calls(&self) -> Calls<'_> {
+        Calls {
             client: self,
+            to: None,
+            from: None,
+            parent_call_sid: None,
+            status: None,
+            start_time: TimeFilter::Any,
+            end_time: TimeFilter::Any,
+            paging: Paging::default(),
         }
     }
 
-    pub fn conference<'a>(&'a self, sid: &'a str) -> GetConference<'a> {
+    pub fn conference<'a>(&'a self, sid: &'a ConferenceSid) -> GetConference<'a> {
         GetConference {
             conference: Conference::new(sid),
             client: self,
@@ -174,10 +420,17 @@ impl Twilio {
     }
 
     pub fn conferences(&self) -> Conferences<'_> {
-        Conferences { client: self }
+        Conferences {
+            client: self,
+            friendly_name: None,
+            status: None,
+            date_created: TimeFilter::Any,
+            date_updated: TimeFilter::Any,
+            paging: Paging::default(),
+        }
     }
 
-    pub fn recording<'a>(&'a self, sid: &'a str) -> GetRecording<'a> {
+    pub fn recording<'a>(&'a self, sid: &'a RecordingSid) -> GetRecording<'a> {
         GetRecording {
             recording: Recording::new(sid),
             client: self,
@@ -185,19 +438,194 @@ impl Twilio {
     }
 
     pub fn recordings(&self) -> Recordings<'_> {
-        Recordings { client: self }
+        Recordings {
+            client: self,
+            call_sid: None,
+            conference_sid: None,
+            date_created: TimeFilter::Any,
+            paging: Paging::default(),
+        }
+    }
+
+    pub fn transcription<'a>(
+        &'a self,
+        transcription_sid: &'a TranscriptionSid,
+    ) -> GetTranscription<'a> {
+        GetTranscription {
+            transcription_sid,
+            client: self,
+        }
+    }
+
+    pub fn transcriptions(&self) -> Transcriptions<'_> {
+        Transcriptions {
+            recording_sid: None,
+            client: self,
+            paging: Paging::default(),
+        }
+    }
+}
+
+pub struct TwilioBuilder {
+    sid: String,
+    token: String,
+    scheme: Option<String>,
+    host: Option<String>,
+    edge: Option<String>,
+    region: Option<String>,
+    base_url: Option<String>,
+    transport: Option<Box<dyn Transport>>,
+    retry: RetryPolicy,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl fmt::Debug for TwilioBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TwilioBuilder")
+            .field("sid", &self.sid)
+            .field("token", &"<redacted>")
+            .field("scheme", &self.scheme)
+            .field("host", &self.host)
+            .field("edge", &self.edge)
+            .field("region", &self.region)
+            .field("base_url", &self.base_url)
+            .field("transport", &self.transport)
+            .field("retry", &self.retry)
+            .field("rate_limiter", &self.rate_limiter)
+            .finish()
     }
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(untagged)]
-pub enum TwilioJson<T> {
-    Success(T),
-    Fail {
-        code: usize,
-        message: String,
-        status: usize,
-    },
+impl TwilioBuilder {
+    pub fn new<S, P>(sid: S, token: P) -> TwilioBuilder
+    where
+        S: Into<String>,
+        P: AsRef<str>,
+    {
+        TwilioBuilder {
+            sid: sid.into(),
+            token: token.as_ref().to_owned(),
+            scheme: None,
+            host: None,
+            edge: None,
+            region: None,
+            base_url: None,
+            transport: None,
+            retry: RetryPolicy::none(),
+            rate_limiter: None,
+        }
+    }
+
+    /// defaults to `https`
+    pub fn scheme<S: Into<String>>(mut self, scheme: S) -> TwilioBuilder {
+        self.scheme = Some(scheme.into());
+        self
+    }
+
+    /// defaults to `api.twilio.com`, may include a port
+    pub fn host<S: Into<String>>(mut self, host: S) -> TwilioBuilder {
+        self.host = Some(host.into());
+        self
+    }
+
+    /// Twilio edge location, e.g. `dublin`. The region defaults to `us1` when
+    /// only an edge is given
+    pub fn edge<S: Into<String>>(mut self, edge: S) -> TwilioBuilder {
+        self.edge = Some(edge.into());
+        self
+    }
+
+    /// Twilio processing region, e.g. `ie1`
+    pub fn region<S: Into<String>>(mut self, region: S) -> TwilioBuilder {
+        self.region = Some(region.into());
+        self
+    }
+
+    /// Use `url` (scheme, host and optional port) verbatim, ignoring scheme,
+    /// host, edge and region
+    pub fn base_url<S: Into<String>>(mut self, url: S) -> TwilioBuilder {
+        self.base_url = Some(url.into());
+        self
+    }
+
+    /// Send requests through `transport` instead of the default
+    /// [`HyperTransport`]
+    pub fn transport<T: Transport + 'static>(mut self, transport: T) -> TwilioBuilder {
+        self.transport = Some(Box::new(transport));
+        self
+    }
+
+    /// Retry rate limited, failed and unreachable requests, see
+    /// [`RetryPolicy`]
+    pub fn retry_policy(mut self, retry: RetryPolicy) -> TwilioBuilder {
+        self.retry = retry;
+        self
+    }
+
+    /// Throttle outgoing messages per sender, the limiter can be shared
+    /// between clients by passing an `Arc<RateLimiter>`
+    pub fn rate_limiter<L: Into<Arc<RateLimiter>>>(mut self, limiter: L) -> TwilioBuilder {
+        self.rate_limiter = Some(limiter.into());
+        self
+    }
+
+    fn resolve_host(&self) -> String {
+        let host = self.host.as_deref().unwrap_or(DEFAULT_HOST);
+        if self.edge.is_none() && self.region.is_none() {
+            return host.to_owned();
+        }
+        // only twilio hosts follow the {product}.{edge}.{region}.twilio.com
+        // scheme, anything else is left alone
+        let pieces = host.split('.').collect::<Vec<_>>();
+        if pieces.len() < 3 || pieces[pieces.len() - 2..] != ["twilio", "com"] {
+            return host.to_owned();
+        }
+        let product = pieces[0];
+        let (mut edge, mut region) = match pieces.len() {
+            5 => (Some(pieces[1]), Some(pieces[2])),
+            4 => (None, Some(pieces[1])),
+            _ => (None, None),
+        };
+        if let Some(ref e) = self.edge {
+            edge = Some(e.as_str());
+        }
+        if let Some(ref r) = self.region {
+            region = Some(r.as_str());
+        }
+        if edge.is_some() && region.is_none() {
+            region = Some(DEFAULT_REGION);
+        }
+        let mut host = vec![product];
+        host.extend(edge);
+        host.extend(region);
+        host.extend(["twilio", "com"]);
+        host.join(".")
+    }
+
+    pub fn build(self) -> TwilioResult<Twilio> {
+        let base = match self.base_url {
+            Some(ref url) => url.trim_end_matches('/').to_owned(),
+            None => format!(
+                "{}://{}",
+                self.scheme.as_deref().unwrap_or(DEFAULT_SCHEME),
+                self.resolve_host()
+            ),
+        };
+        // make sure we will be able to build request uris later on
+        base.parse::<hyper::Uri>()?;
+        let transport = self
+            .transport
+            .unwrap_or_else(|| Box::new(HyperTransport::new()));
+
+        Ok(Twilio {
+            auth: Authorization(Credentials::basic(&self.sid, &self.token)?),
+            sid: self.sid,
+            base,
+            transport,
+            retry: self.retry,
+            rate_limiter: self.rate_limiter,
+        })
+    }
 }
 
 #[async_trait]
@@ -210,12 +638,17 @@ pub trait Execute {
     ) -> Result<Request<Body>, TwilioErr>
     where
         U: AsRef<str>;
-    async fn execute<U, D>(
-        &self,
-        method: Method,
-        url: U,
-        body: Option<String>,
-    ) -> TwilioResp<TwilioJson<D>>
+    /// sent as `I-Twilio-Idempotency-Token` when set, and lets the retry
+    /// policy resend a `POST` after a 429 or 5xx
+    fn idempotency_token(&self) -> Option<&str> {
+        None
+    }
+    /// limiter and sender key every attempt, retries included, waits on
+    fn rate_limit(&self) -> Option<(&RateLimiter, String)> {
+        None
+    }
+
+    async fn execute<U, D>(&self, method: Method, url: U, body: Option<String>) -> TwilioResp<D>
     where
         U: AsRef<str> + Send,
         D: for<'de> serde::Deserialize<'de>;
@@ -224,7 +657,7 @@ pub trait Execute {
 #[async_trait]
 pub trait TwilioRequest: Execute {
     type Resp: for<'de> serde::Deserialize<'de>;
-    async fn run(&self) -> TwilioResp<TwilioJson<Self::Resp>>;
+    async fn run(&self) -> TwilioResp<Self::Resp>;
 }
 
 pub fn encode_pairs<I, K, V>(pairs: I) -> Option<String>
@@ -236,13 +669,15 @@ where
 {
     let mut partial = form_urlencoded::Serializer::new(String::new());
     for pair in pairs {
-        let &(ref k, ref v) = pair.borrow();
+        let (k, v) = pair.borrow();
         partial.append_pair(k.as_ref(), v.as_ref());
     }
     let encoded = partial.finish();
     Some(encoded)
 }
 
+/// Joins `pairs` as `k=v&` without percent-encoding them
+#[deprecated(note = "use `encode_pairs`, which percent-encodes keys and values")]
 pub fn url_encode<I, K, V>(pairs: I) -> String
 where
     K: AsRef<str>,
@@ -253,7 +688,7 @@ where
     pairs
         .into_iter()
         .map(|pair| {
-            let &(ref k, ref v) = pair.borrow();
+            let (k, v) = pair.borrow();
             format!("{}={}", k.as_ref(), v.as_ref())
         })
         .fold(String::new(), |mut acc, item| {
@@ -262,3 +697,25 @@ where
             acc
         })
 }
+
+pub(crate) fn bool_str(b: bool) -> &'static str {
+    if b {
+        "true"
+    } else {
+        "false"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Twilio;
+
+    #[test]
+    fn debug_hides_token() {
+        let builder = Twilio::builder("AC1", "s3cr3t");
+        assert!(!format!("{:?}", builder).contains("s3cr3t"));
+        let twilio = builder.build().unwrap();
+        // basic auth is `AC1:s3cr3t` base64 encoded
+        assert!(!format!("{:?}", twilio).contains("QUMxOnMzY3IzdA"));
+    }
+}
@@ -1,7 +1,33 @@
 macro_rules! execute {
     ($ty:tt) => {
+        execute!(@impl $ty);
+    };
+    // requests that carry an `idempotency_token` field send it as a header,
+    // and may be retried like a GET once it is set
+    ($ty:tt, idempotency_token) => {
+        execute!(@impl $ty
+            fn idempotency_token(&self) -> Option<&str> {
+                self.idempotency_token
+            }
+        );
+    };
+    // `SendMsg` also waits on its rate limiter before every attempt
+    ($ty:tt, idempotency_token, rate_limit) => {
+        execute!(@impl $ty
+            fn idempotency_token(&self) -> Option<&str> {
+                self.idempotency_token
+            }
+
+            fn rate_limit(&self) -> Option<(&crate::RateLimiter, String)> {
+                self.limiter()
+            }
+        );
+    };
+    (@impl $ty:tt $($item:item)*) => {
         #[async_trait]
         impl<'a> Execute for $ty<'a> {
+            $($item)*
+
             fn request<U>(
                 &self,
                 method: Method,
@@ -16,10 +42,8 @@ macro_rules! execute {
                     Request,
                 };
                 use typed_headers::HeaderMapExt;
-                const BASE: &str = "https://api.twilio.com/2010-04-01/Accounts";
 
-                let url = format!("{}/{}/{}", BASE, self.client.sid, url.as_ref())
-                    .parse::<hyper::Uri>()?;
+                let url = self.client.url(url).parse::<hyper::Uri>()?;
                 let mut request = Request::builder().method(method).uri(url);
 
                 let mut hmap = HeaderMap::new();
@@ -27,6 +51,9 @@ macro_rules! execute {
                 for (k, v) in hmap {
                     request = request.header(k.unwrap().as_str(), v);
                 }
+                if let Some(token) = self.idempotency_token() {
+                    request = request.header("I-Twilio-Idempotency-Token", token);
+                }
                 Ok(match body {
                     Some(body) => request
                         .header(
@@ -43,27 +70,63 @@ macro_rules! execute {
                 method: Method,
                 url: U,
                 body: Option<String>,
-            ) -> TwilioResp<crate::TwilioJson<D>>
+            ) -> TwilioResp<D>
             where
                 U: AsRef<str> + Send,
                 D: for<'de> serde::Deserialize<'de>,
             {
-                use bytes::Buf;
                 use serde_json;
 
-                let req = self.request(method, url, body)?;
+                let url = url.as_ref().to_owned();
+                let idempotent = method != Method::POST || self.idempotency_token().is_some();
+                let limit = self.rate_limit();
+                let limit = limit.as_ref().map(|(limiter, sender)| (*limiter, sender.as_str()));
+                let res = crate::retry::send(self.client, idempotent, limit, || {
+                    self.request(method.clone(), &url, body.clone())
+                })
+                .await?;
+                let status = res.status();
+                let body = hyper::body::to_bytes(res).await?;
 
-                let res = self
-                    .client
-                    .client
-                    .request(req)
-                    .await
-                    .map_err(TwilioErr::NetworkErr)?;
+                if !status.is_success() {
+                    return Err(crate::ApiError::from_response(status, &body).into());
+                }
+                // DELETE & friends answer with 204 and no body at all
+                if body.is_empty() {
+                    return Ok(serde_json::from_slice(b"null")?);
+                }
+                Ok(serde_json::from_slice(&body)?)
+            }
+        }
+    };
+}
+
+macro_rules! paginate {
+    ($ty:tt, $page:ty) => {
+        impl<'a> $ty<'a> {
+            pub fn page_size(mut self, page_size: usize) -> $ty<'a> {
+                self.paging.page_size = Some(page_size);
+                self
+            }
 
-                let body = hyper::body::aggregate(res).await?;
+            /// stop after `limit` items when streaming
+            pub fn limit(mut self, limit: usize) -> $ty<'a> {
+                self.paging.limit = Some(limit);
+                self
+            }
+
+            /// Every item across all pages, later pages are only fetched once
+            /// the stream gets to them
+            pub fn stream(
+                &self,
+            ) -> impl futures::Stream<Item = TwilioResp<<$page as crate::Page>::Item>> + Send + '_
+            {
+                crate::page::paginate::<_, $page>(self, self.list_url(), self.paging)
+            }
 
-                let json_resp = serde_json::from_reader(body.reader())?;
-                Ok(json_resp)
+            pub async fn collect_all(&self) -> TwilioResp<Vec<<$page as crate::Page>::Item>> {
+                use futures::TryStreamExt;
+                self.stream().try_collect().await
             }
         }
     };
@@ -1,49 +1,129 @@
 use super::{
-    encode_pairs, url_encode, Execute, Twilio, TwilioErr, TwilioJson, TwilioRequest, TwilioResp,
+    bool_str, encode_pairs, AccountSid, Address, DateTime, Execute, FilterDate, MediaSid,
+    MessageSid, MessagingServiceSid, Page, Paging, RateLimiter, TimeFilter, Twilio, TwilioErr,
+    TwilioRequest, TwilioResp,
 };
+use crate::phone::{self, IntoAddress, ParsePhoneError};
+#[cfg(feature = "rust_decimal")]
+use crate::Price;
 use async_trait::async_trait;
 use hyper::{self, Method};
-use serde::Deserialize;
+use serde::{de, Deserialize, Deserializer};
+use std::fmt;
 
-#[derive(Default, Debug)]
+#[derive(Debug)]
 pub struct Msg<'a> {
-    from: &'a str,
-    to: &'a str,
+    from: Option<Address>,
+    to: Address,
+    invalid: Option<ParsePhoneError>,
     body: &'a str,
-    media_url: Option<&'a str>,
+    media_url: Vec<&'a str>,
+    messaging_service_sid: Option<&'a str>,
+    status_callback: Option<&'a str>,
+    validity_period: Option<u32>,
+    send_at: Option<&'a str>,
+    max_price: Option<&'a str>,
+    provide_feedback: Option<bool>,
+    attempt: Option<u32>,
+    smart_encoded: Option<bool>,
+    shorten_urls: Option<bool>,
+    persistent_action: Vec<&'a str>,
+    content_sid: Option<&'a str>,
+    content_variables: Option<&'a str>,
 }
 
 impl<'a> Msg<'a> {
-    pub fn new(from: &'a str, to: &'a str, body: &'a str) -> Msg<'a> {
+    pub fn new(from: impl IntoAddress, to: impl IntoAddress, body: &'a str) -> Msg<'a> {
+        let mut invalid = None;
         Msg {
-            from,
-            to,
+            from: Some(phone::checked(from, &mut invalid)),
+            to: phone::checked(to, &mut invalid),
+            invalid,
             body,
-            ..Msg::default()
+            media_url: Vec::new(),
+            messaging_service_sid: None,
+            status_callback: None,
+            validity_period: None,
+            send_at: None,
+            max_price: None,
+            provide_feedback: None,
+            attempt: None,
+            smart_encoded: None,
+            shorten_urls: None,
+            persistent_action: Vec::new(),
+            content_sid: None,
+            content_variables: None,
+        }
+    }
+
+    /// key the rate limiter throttles this message under
+    fn sender(&self) -> String {
+        match (self.messaging_service_sid, &self.from) {
+            (Some(sid), _) => sid.to_owned(),
+            (None, Some(from)) => from.to_string(),
+            (None, None) => String::new(),
         }
     }
 }
 
-impl<'a> ToString for Msg<'a> {
-    fn to_string(&self) -> String {
-        match self.media_url {
-            Some(m_url) => encode_pairs(&[
-                ("To", self.to),
-                ("From", self.from),
-                ("Body", self.body),
-                ("MediaUrl", m_url),
-            ])
-            .unwrap(),
-            None => {
-                encode_pairs(&[("To", self.to), ("From", self.from), ("Body", self.body)]).unwrap()
+impl<'a> fmt::Display for Msg<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let validity_period = self.validity_period.map(|p| p.to_string());
+        let attempt = self.attempt.map(|a| a.to_string());
+
+        let to = self.to.to_string();
+        let from = self.from.as_ref().map(|from| from.to_string());
+
+        let mut pairs = vec![("To", to.as_str())];
+        if let Some(ref from) = from {
+            pairs.push(("From", from));
+        }
+        pair!(self, messaging_service_sid, "MessagingServiceSid", pairs);
+        // content templates bring their own body
+        if !self.body.is_empty() {
+            pairs.push(("Body", self.body));
+        }
+        for url in &self.media_url {
+            pairs.push(("MediaUrl", url));
+        }
+        pair!(self, content_sid, "ContentSid", pairs);
+        pair!(self, content_variables, "ContentVariables", pairs);
+        pair!(self, status_callback, "StatusCallback", pairs);
+        if let Some(send_at) = self.send_at {
+            pairs.push(("ScheduleType", "fixed"));
+            pairs.push(("SendAt", send_at));
+        }
+        pair!(self, max_price, "MaxPrice", pairs);
+        for action in &self.persistent_action {
+            pairs.push(("PersistentAction", action));
+        }
+        if let Some(ref period) = validity_period {
+            pairs.push(("ValidityPeriod", period));
+        }
+        if let Some(ref attempt) = attempt {
+            pairs.push(("Attempt", attempt));
+        }
+        let flags = [
+            ("ProvideFeedback", self.provide_feedback),
+            ("SmartEncoded", self.smart_encoded),
+            ("ShortenUrls", self.shorten_urls),
+        ];
+        for (name, flag) in flags.iter() {
+            if let Some(flag) = flag {
+                pairs.push((name, bool_str(*flag)));
             }
         }
+
+        f.write_str(&encode_pairs(pairs).unwrap())
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 #[allow(non_camel_case_types)]
 pub enum MsgStatus {
+    accepted,
+    scheduled,
+    canceled,
     queued,
     sending,
     sent,
@@ -52,6 +132,28 @@ pub enum MsgStatus {
     undelivered,
     receiving,
     received,
+    read,
+    partially_delivered,
+}
+
+impl MsgStatus {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match *self {
+            MsgStatus::accepted => "accepted",
+            MsgStatus::scheduled => "scheduled",
+            MsgStatus::canceled => "canceled",
+            MsgStatus::queued => "queued",
+            MsgStatus::sending => "sending",
+            MsgStatus::sent => "sent",
+            MsgStatus::failed => "failed",
+            MsgStatus::delivered => "delivered",
+            MsgStatus::undelivered => "undelivered",
+            MsgStatus::receiving => "receiving",
+            MsgStatus::received => "received",
+            MsgStatus::read => "read",
+            MsgStatus::partially_delivered => "partially_delivered",
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -59,38 +161,172 @@ pub struct MsgResp {
     pub from: String,
     pub to: String,
     pub body: String,
-    pub sid: String,
+    pub sid: MessageSid,
     pub status: MsgStatus,
-    pub media_url: Option<String>,
+    /// Twilio sends this as a string, e.g. `"2"`
+    #[serde(deserialize_with = "num_from_str")]
+    pub num_media: usize,
+    #[cfg(not(feature = "rust_decimal"))]
     pub price: Option<String>,
+    #[cfg(not(feature = "rust_decimal"))]
     pub price_unit: String,
+    #[cfg(feature = "rust_decimal")]
+    #[serde(flatten, deserialize_with = "crate::price::deserialize")]
+    pub price: Option<Price>,
     pub uri: String,
-    pub date_created: String,
-    pub date_sent: Option<String>,
-    pub date_updated: String,
+    #[serde(deserialize_with = "crate::datetime::deserialize")]
+    pub date_created: DateTime,
+    #[serde(default, deserialize_with = "crate::datetime::deserialize_opt")]
+    pub date_sent: Option<DateTime>,
+    #[serde(deserialize_with = "crate::datetime::deserialize")]
+    pub date_updated: DateTime,
+}
+
+fn num_from_str<'de, D>(de: D) -> Result<usize, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Num {
+        Str(String),
+        Int(usize),
+    }
+    match Num::deserialize(de)? {
+        Num::Str(s) => s.parse().map_err(de::Error::custom),
+        Num::Int(n) => Ok(n),
+    }
 }
 
+/// most media urls Twilio accepts on one message
+pub const MAX_MEDIA: usize = 10;
+
 // for outbound sms
 #[derive(Debug)]
 pub struct SendMsg<'a> {
     pub msg: Msg<'a>,
+    pub idempotency_token: Option<&'a str>,
+    pub rate_limiter: Option<&'a RateLimiter>,
     pub client: &'a Twilio,
 }
 
 impl<'a> SendMsg<'a> {
+    /// the limiter to wait on and the sender key it throttles this message by
+    fn limiter(&self) -> Option<(&RateLimiter, String)> {
+        self.rate_limiter
+            .or(self.client.rate_limiter.as_deref())
+            .map(|limiter| (limiter, self.msg.sender()))
+    }
+
+    /// Throttle this message with `limiter` instead of the client's limiter,
+    /// retries wait for a slot too
+    pub fn rate_limiter(mut self, limiter: &'a RateLimiter) -> SendMsg<'a> {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Lets the retry policy resend the message after a 429 or 5xx, not only
+    /// when the connection failed. The token is sent as
+    /// `I-Twilio-Idempotency-Token`, but Twilio does not deduplicate on it, so
+    /// only set it when a duplicate message is acceptable
+    pub fn idempotency_token(mut self, token: &'a str) -> SendMsg<'a> {
+        self.idempotency_token = Some(token);
+        self
+    }
+
+    /// Attach a media url, may be given up to [`MAX_MEDIA`] times
     pub fn media(mut self, media_url: &'a str) -> SendMsg<'a> {
-        self.msg.media_url = Some(media_url);
+        self.msg.media_url.push(media_url);
+        self
+    }
+
+    /// Send through a messaging service, which picks the `From` number
+    pub fn messaging_service_sid(mut self, sid: &'a MessagingServiceSid) -> SendMsg<'a> {
+        self.msg.messaging_service_sid = Some(sid.as_str());
+        self.msg.from = None;
+        self
+    }
+
+    pub fn status_callback(mut self, callback: &'a str) -> SendMsg<'a> {
+        self.msg.status_callback = Some(callback);
+        self
+    }
+
+    /// seconds the message may wait in the queue before it is dropped
+    pub fn validity_period(mut self, seconds: u32) -> SendMsg<'a> {
+        self.msg.validity_period = Some(seconds);
+        self
+    }
+
+    /// Schedule the message (`ScheduleType=fixed`) for `send_at`, an ISO 8601
+    /// time between 15 minutes and 35 days out. Requires a messaging service.
+    pub fn send_at(mut self, send_at: &'a str) -> SendMsg<'a> {
+        self.msg.send_at = Some(send_at);
+        self
+    }
+
+    /// most you are willing to pay for the message, e.g. `"0.05"`
+    pub fn max_price(mut self, max_price: &'a str) -> SendMsg<'a> {
+        self.msg.max_price = Some(max_price);
+        self
+    }
+
+    /// track delivery confirmation through [`GetMessage::feedback`]
+    pub fn provide_feedback(mut self, feedback: bool) -> SendMsg<'a> {
+        self.msg.provide_feedback = Some(feedback);
+        self
+    }
+
+    /// total number of attempts made to send the message
+    pub fn attempt(mut self, attempt: u32) -> SendMsg<'a> {
+        self.msg.attempt = Some(attempt);
+        self
+    }
+
+    /// replace unicode lookalikes so the body fits in GSM-7
+    pub fn smart_encoded(mut self, smart_encoded: bool) -> SendMsg<'a> {
+        self.msg.smart_encoded = Some(smart_encoded);
+        self
+    }
+
+    /// shorten links in the body with the messaging service's domain
+    pub fn shorten_urls(mut self, shorten_urls: bool) -> SendMsg<'a> {
+        self.msg.shorten_urls = Some(shorten_urls);
+        self
+    }
+
+    /// an action url (`mailto:`, `geo:`, ...), may be given more than once
+    pub fn persistent_action(mut self, action: &'a str) -> SendMsg<'a> {
+        self.msg.persistent_action.push(action);
+        self
+    }
+
+    /// Send a content template instead of a body
+    pub fn content_sid(mut self, sid: &'a str) -> SendMsg<'a> {
+        self.msg.content_sid = Some(sid);
+        self
+    }
+
+    /// JSON object of the template's placeholder values, e.g. `{"1": "Bob"}`
+    pub fn content_variables(mut self, variables: &'a str) -> SendMsg<'a> {
+        self.msg.content_variables = Some(variables);
         self
     }
 }
 
-execute!(SendMsg);
+execute!(SendMsg, idempotency_token, rate_limit);
 
 #[async_trait]
 impl<'a> TwilioRequest for SendMsg<'a> {
     type Resp = MsgResp;
 
-    async fn run(&self) -> TwilioResp<TwilioJson<Self::Resp>> {
+    async fn run(&self) -> TwilioResp<Self::Resp> {
+        if let Some(ref e) = self.msg.invalid {
+            return Err(TwilioErr::PhoneErr(e.clone()));
+        }
+        if self.msg.media_url.len() > MAX_MEDIA {
+            return Err(TwilioErr::TooManyMedia(self.msg.media_url.len()));
+        }
         let msg = self.msg.to_string();
         self.execute(Method::POST, "Messages.json", Some(msg)).await
     }
@@ -98,7 +334,7 @@ impl<'a> TwilioRequest for SendMsg<'a> {
 
 #[derive(Debug)]
 pub struct GetMessage<'a> {
-    pub message_sid: &'a str,
+    pub message_sid: &'a MessageSid,
     pub client: &'a Twilio,
 }
 
@@ -108,117 +344,215 @@ execute!(GetMessage);
 impl<'a> TwilioRequest for GetMessage<'a> {
     type Resp = MsgResp;
 
-    async fn run(&self) -> TwilioResp<TwilioJson<Self::Resp>> {
+    async fn run(&self) -> TwilioResp<Self::Resp> {
         let msg_sid = format!("Messages/{}.json", self.message_sid);
         self.execute(Method::GET, msg_sid, None).await
     }
 }
 
 impl<'a> GetMessage<'a> {
-    pub async fn redact(&self) -> TwilioResp<TwilioJson<MsgResp>> {
+    pub async fn redact(&self) -> TwilioResp<MsgResp> {
         let msg_sid = format!("Messages/{}.json", self.message_sid);
         self.execute(Method::POST, msg_sid, Some("Body=".into()))
             .await
     }
 
-    pub async fn delete(&self) -> TwilioResp<TwilioJson<MsgResp>> {
+    /// Modify the body or status of the message
+    pub fn update(&self) -> UpdateMessage<'a> {
+        UpdateMessage {
+            message_sid: self.message_sid,
+            body: None,
+            status: None,
+            client: self.client,
+        }
+    }
+
+    /// Cancel a scheduled message before it is sent
+    pub async fn cancel(&self) -> TwilioResp<MsgResp> {
+        self.update().status(MsgStatus::canceled).run().await
+    }
+
+    /// Report whether the recipient acted on the message, needs the message to
+    /// be sent with `provide_feedback(true)`
+    pub async fn feedback(&self, outcome: FeedbackOutcome) -> TwilioResp<FeedbackResp> {
+        let url = format!("Messages/{}/Feedback.json", self.message_sid);
+        let outcome = match outcome {
+            FeedbackOutcome::confirmed => "Outcome=confirmed",
+            FeedbackOutcome::unconfirmed => "Outcome=unconfirmed",
+        };
+        self.execute(Method::POST, url, Some(outcome.into())).await
+    }
+
+    pub async fn delete(&self) -> TwilioResp<()> {
         let msg_sid = format!("Messages/{}.json", self.message_sid);
         self.execute(Method::DELETE, msg_sid, None).await
     }
 
-    pub async fn media(&self) -> TwilioResp<TwilioJson<MediaResp>> {
+    pub async fn media(&self) -> TwilioResp<MediaResp> {
         let msg_sid = format!("Messages/{}/Media.json", self.message_sid);
         self.execute(Method::GET, msg_sid, None).await
     }
 }
 
+#[derive(Debug)]
+pub struct UpdateMessage<'a> {
+    pub message_sid: &'a MessageSid,
+    pub body: Option<&'a str>,
+    pub status: Option<MsgStatus>,
+    pub client: &'a Twilio,
+}
+
+impl<'a> UpdateMessage<'a> {
+    /// an empty body redacts the message
+    pub fn body(mut self, body: &'a str) -> UpdateMessage<'a> {
+        self.body = Some(body);
+        self
+    }
+
+    /// only `canceled` is accepted, and only for scheduled messages
+    pub fn status(mut self, status: MsgStatus) -> UpdateMessage<'a> {
+        self.status = Some(status);
+        self
+    }
+}
+
+impl<'a> fmt::Display for UpdateMessage<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut pairs = Vec::new();
+        pair!(self, body, "Body", pairs);
+        if let Some(status) = self.status {
+            pairs.push(("Status", status.as_str()));
+        }
+        f.write_str(&encode_pairs(pairs).unwrap())
+    }
+}
+
+execute!(UpdateMessage);
+
+#[async_trait]
+impl<'a> TwilioRequest for UpdateMessage<'a> {
+    type Resp = MsgResp;
+
+    async fn run(&self) -> TwilioResp<Self::Resp> {
+        let msg_sid = format!("Messages/{}.json", self.message_sid);
+        self.execute(Method::POST, msg_sid, Some(self.to_string()))
+            .await
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[allow(non_camel_case_types)]
+pub enum FeedbackOutcome {
+    confirmed,
+    unconfirmed,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FeedbackResp {
+    pub account_sid: AccountSid,
+    pub message_sid: MessageSid,
+    pub outcome: FeedbackOutcome,
+    #[serde(deserialize_with = "crate::datetime::deserialize")]
+    pub date_created: DateTime,
+    #[serde(deserialize_with = "crate::datetime::deserialize")]
+    pub date_updated: DateTime,
+    pub uri: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct MediaResp {
     pub media_list: Vec<MediaItem>,
-    pub num_pages: i32,
-    pub page: i32,
-    pub page_size: i32,
-    pub start: i32,
-    pub total: i32,
+    pub page: usize,
+    pub page_size: usize,
+    pub start: usize,
+    pub end: usize,
     pub uri: String,
-    pub account_sid: String,
-    pub message_sid: String,
+    pub first_page_uri: Option<String>,
+    pub next_page_uri: Option<String>,
+    pub previous_page_uri: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct MediaItem {
-    pub account_sid: String,
+    pub account_sid: AccountSid,
     pub content_type: String,
-    pub sid: String,
+    pub sid: MediaSid,
     pub uri: String,
-    pub message_sid: String,
-    pub date_created: String,
-    pub date_update: String,
+    /// the message the media belongs to
+    pub parent_sid: MessageSid,
+    #[serde(deserialize_with = "crate::datetime::deserialize")]
+    pub date_created: DateTime,
+    #[serde(deserialize_with = "crate::datetime::deserialize")]
+    pub date_updated: DateTime,
 }
 
+/// `between` and `on` used to return this, they return [`Messages`] now
+#[deprecated(note = "use `Messages`")]
+pub type MessagesDetails<'a> = Messages<'a>;
+
 #[derive(Debug)]
 pub struct Messages<'a> {
     pub client: &'a Twilio,
+    pub to: Option<&'a str>,
+    pub from: Option<&'a str>,
+    pub date_sent: TimeFilter<'a>,
+    pub paging: Paging,
 }
 
 impl<'a> Messages<'a> {
-    pub fn between(self, from: &'a str, to: &'a str) -> MessagesDetails<'a> {
-        MessagesDetails {
-            client: self.client,
-            from: Some(from),
-            to: Some(to),
-            date_sent: None,
-        }
+    pub fn to(mut self, to: &'a str) -> Messages<'a> {
+        self.to = Some(to);
+        self
     }
 
-    pub fn on(self, date_sent: &'a str) -> MessagesDetails<'a> {
-        MessagesDetails {
-            client: self.client,
-            from: None,
-            to: None,
-            date_sent: Some(date_sent),
-        }
+    pub fn from(mut self, from: &'a str) -> Messages<'a> {
+        self.from = Some(from);
+        self
     }
-}
 
-execute!(Messages);
-
-#[async_trait]
-impl<'a> TwilioRequest for Messages<'a> {
-    type Resp = ListAllMsgs;
+    /// Only messages sent within `filter`, dates are `YYYY-MM-DD`. Replaces
+    /// any date filter set before, as do `between` and `on`
+    pub fn date_sent(mut self, filter: TimeFilter<'a>) -> Messages<'a> {
+        self.date_sent = filter;
+        self
+    }
 
-    async fn run(&self) -> TwilioResp<TwilioJson<Self::Resp>> {
-        self.execute(Method::GET, "Messages.json", None).await
+    /// Only messages sent after `start` and before `end`
+    pub fn between(
+        self,
+        start: impl Into<FilterDate<'a>>,
+        end: impl Into<FilterDate<'a>>,
+    ) -> Messages<'a> {
+        self.date_sent(TimeFilter::between(start, end))
     }
-}
 
-#[derive(Debug)]
-pub struct MessagesDetails<'a> {
-    pub client: &'a Twilio,
-    pub from: Option<&'a str>,
-    pub to: Option<&'a str>,
-    pub date_sent: Option<&'a str>,
-}
+    /// Only messages sent on `date_sent`
+    pub fn on(self, date_sent: impl Into<FilterDate<'a>>) -> Messages<'a> {
+        self.date_sent(TimeFilter::on(date_sent))
+    }
 
-impl<'a> ToString for MessagesDetails<'a> {
-    fn to_string(&self) -> String {
+    fn list_url(&self) -> String {
         let mut pairs = Vec::new();
-        pair!(self, from, "From", pairs);
         pair!(self, to, "To", pairs);
-        pair!(self, date_sent, "DateSent", pairs);
-        // does this have to be different? will the encode_pairs work here?
-        url_encode(pairs)
+        pair!(self, from, "From", pairs);
+        self.date_sent
+            .pairs(["DateSent", "DateSent<", "DateSent>"], &mut pairs);
+        match encode_pairs(pairs) {
+            Some(query) if !query.is_empty() => format!("Messages.json?{}", query),
+            _ => "Messages.json".into(),
+        }
     }
 }
 
-execute!(MessagesDetails);
+execute!(Messages);
+paginate!(Messages, ListAllMsgs);
 
 #[async_trait]
-impl<'a> TwilioRequest for MessagesDetails<'a> {
+impl<'a> TwilioRequest for Messages<'a> {
     type Resp = ListAllMsgs;
 
-    async fn run(&self) -> TwilioResp<TwilioJson<Self::Resp>> {
-        let url = format!("Messages.json?{}", self.to_string());
+    async fn run(&self) -> TwilioResp<Self::Resp> {
+        let url = self.paging.url(self.list_url());
         self.execute(Method::GET, url, None).await
     }
 }
@@ -232,3 +566,151 @@ pub struct ListAllMsgs {
     pub next_page_uri: Option<String>,
     pub previous_page_uri: Option<String>,
 }
+
+impl Page for ListAllMsgs {
+    type Item = MsgResp;
+
+    fn next_page_uri(&self) -> Option<&str> {
+        self.next_page_uri.as_deref()
+    }
+
+    fn into_items(self) -> Vec<MsgResp> {
+        self.messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::Canned;
+
+    #[test]
+    fn messaging_service_replaces_from() {
+        let msg = Msg {
+            from: None,
+            messaging_service_sid: Some("MG123"),
+            send_at: Some("2030-01-01T00:00:00Z"),
+            validity_period: Some(600),
+            shorten_urls: Some(true),
+            persistent_action: vec!["mailto:a@b.com", "geo:1,2"],
+            ..Msg::new("+15005550006", "+14155550100", "hi")
+        };
+        assert_eq!(msg.sender(), "MG123");
+        assert_eq!(
+            msg.to_string(),
+            "To=%2B14155550100&MessagingServiceSid=MG123&Body=hi&ScheduleType=fixed\
+             &SendAt=2030-01-01T00%3A00%3A00Z&PersistentAction=mailto%3Aa%40b.com\
+             &PersistentAction=geo%3A1%2C2&ValidityPeriod=600&ShortenUrls=true"
+        );
+    }
+
+    #[test]
+    fn repeats_media_url() {
+        let msg = Msg {
+            media_url: vec!["https://a.com/1.jpg", "https://a.com/2.jpg"],
+            ..Msg::new("+15005550006", "+14155550100", "")
+        };
+        assert_eq!(
+            msg.to_string(),
+            "To=%2B14155550100&From=%2B15005550006&MediaUrl=https%3A%2F%2Fa.com%2F1.jpg\
+             &MediaUrl=https%3A%2F%2Fa.com%2F2.jpg"
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_too_many_media() {
+        let client = Twilio::new("AC123", "token").unwrap();
+        let mut send = client.send_msg("+15005550006", "+14155550100", "hi");
+        for _ in 0..=MAX_MEDIA {
+            send = send.media("https://a.com/1.jpg");
+        }
+        match send.run().await {
+            Err(TwilioErr::TooManyMedia(11)) => {}
+            other => panic!("expected TooManyMedia, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn encodes_list_filters() {
+        let client = Twilio::new("AC123", "token").unwrap();
+        let msgs = client
+            .msgs()
+            .to("+14155550100")
+            .between("2020-01-01", "2020-01-31")
+            .page_size(50);
+        assert_eq!(
+            msgs.paging.url(msgs.list_url()),
+            "Messages.json?To=%2B14155550100&DateSent%3E=2020-01-01\
+             &DateSent%3C=2020-01-31&PageSize=50"
+        );
+        assert_eq!(client.msgs().list_url(), "Messages.json");
+    }
+
+    #[tokio::test]
+    async fn rejects_malformed_number() {
+        let canned = Canned::new(200, "{}");
+        let client = canned.client();
+        match client.send_msg("+1 415 55", "MyBrand", "hi").run().await {
+            Err(TwilioErr::PhoneErr(ParsePhoneError::Length(6))) => {}
+            other => panic!("expected PhoneErr, got {:?}", other),
+        }
+        assert!(canned.seen().is_empty());
+    }
+
+    #[tokio::test]
+    async fn updates_message() {
+        let canned = Canned::new(
+            200,
+            r#"{"from": "+15005550006", "to": "+14155550100", "body": "", "price": null,
+            "sid": "SM00000000000000000000000000000001", "status": "canceled", "num_media": "0",
+            "price_unit": "USD", "uri": "/uri", "date_created": "Tue, 31 Aug 2010 20:36:28 +0000",
+            "date_sent": null, "date_updated": "Tue, 31 Aug 2010 20:36:28 +0000"}"#,
+        );
+        let twilio = canned.client();
+        let sid = "SM00000000000000000000000000000001".parse().unwrap();
+        let msg = twilio.msg(&sid);
+        msg.cancel().await.unwrap();
+        msg.update().body("").run().await.unwrap();
+
+        let url = "POST https://api.twilio.com/2010-04-01/Accounts/AC1/Messages/\
+                   SM00000000000000000000000000000001";
+        assert_eq!(canned.seen(), vec![format!("{}.json", url); 2]);
+        assert_eq!(canned.bodies(), ["Status=canceled", "Body="]);
+
+        let canned = Canned::new(
+            201,
+            r#"{"account_sid": "AC00000000000000000000000000000001",
+            "message_sid": "SM00000000000000000000000000000001", "outcome": "confirmed",
+            "date_created": "Tue, 31 Aug 2010 20:36:28 +0000",
+            "date_updated": "Tue, 31 Aug 2010 20:36:28 +0000", "uri": "/uri"}"#,
+        );
+        let twilio = canned.client();
+        let feedback = twilio
+            .msg(&sid)
+            .feedback(FeedbackOutcome::confirmed)
+            .await
+            .unwrap();
+        assert_eq!(feedback.outcome, FeedbackOutcome::confirmed);
+        assert_eq!(canned.seen(), [format!("{}/Feedback.json", url)]);
+        assert_eq!(canned.bodies(), ["Outcome=confirmed"]);
+    }
+
+    #[tokio::test]
+    async fn lists_media() {
+        let canned = Canned::new(
+            200,
+            r#"{"end": 0, "first_page_uri": "/media.json?Page=0", "next_page_uri": null,
+            "page": 0, "page_size": 50, "previous_page_uri": null, "start": 0, "uri": "/media.json",
+            "media_list": [{"account_sid": "AC00000000000000000000000000000001",
+            "content_type": "image/jpeg", "date_created": "Tue, 31 Aug 2010 20:36:28 +0000",
+            "date_updated": "Tue, 31 Aug 2010 20:36:28 +0000",
+            "parent_sid": "MM00000000000000000000000000000001",
+            "sid": "ME00000000000000000000000000000001", "uri": "/media/ME1.json"}]}"#,
+        );
+        let twilio = canned.client();
+        let sid = "MM00000000000000000000000000000001".parse().unwrap();
+        let media = twilio.msg(&sid).media().await.unwrap();
+        assert_eq!(media.media_list[0].parent_sid, sid);
+        assert_eq!(media.media_list[0].content_type, "image/jpeg");
+    }
+}
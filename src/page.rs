@@ -0,0 +1,148 @@
+use super::{Execute, TwilioErr, TwilioResp};
+use futures::{stream, Stream, StreamExt, TryStreamExt};
+use hyper::Method;
+use serde::Deserialize;
+
+/// A single page returned by one of the list endpoints
+pub trait Page: for<'de> Deserialize<'de> + Send {
+    type Item: Send;
+
+    /// path of the next page relative to the api host, `None` on the last page
+    fn next_page_uri(&self) -> Option<&str>;
+    fn into_items(self) -> Vec<Self::Item>;
+}
+
+/// largest `PageSize` Twilio accepts
+const MAX_PAGE_SIZE: usize = 1000;
+
+/// `PageSize` and item limit shared by every list request
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Paging {
+    pub page_size: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+impl Paging {
+    /// Twilio rejects a `PageSize` over 1000, and there is no point asking for
+    /// a page bigger than the number of items we are going to keep
+    fn page_size(&self) -> Option<usize> {
+        let size = match (self.page_size, self.limit) {
+            (Some(size), Some(limit)) => Some(size.min(limit)),
+            (size, _) => size,
+        };
+        size.map(|size| size.min(MAX_PAGE_SIZE))
+    }
+
+    pub(crate) fn url(&self, url: String) -> String {
+        match self.page_size() {
+            Some(size) => {
+                let sep = match url.chars().last() {
+                    Some('?') | Some('&') => "",
+                    _ if url.contains('?') => "&",
+                    _ => "?",
+                };
+                format!("{}{}PageSize={}", url, sep, size)
+            }
+            None => url,
+        }
+    }
+}
+
+/// Lazily walk every page starting at `url`, following `next_page_uri` until
+/// it runs out or `limit` items have been yielded.
+pub(crate) fn paginate<'a, E, P>(
+    req: &'a E,
+    url: String,
+    paging: Paging,
+) -> impl Stream<Item = TwilioResp<P::Item>> + Send + 'a
+where
+    E: Execute + Sync,
+    P: Page + 'a,
+{
+    stream::try_unfold(Some(paging.url(url)), move |next| async move {
+        let url = match next {
+            Some(url) => url,
+            None => return Ok(None),
+        };
+        let page = req.execute::<_, P>(Method::GET, url, None).await?;
+        let next = page.next_page_uri().map(|uri| uri.to_owned());
+        let items = page.into_items().into_iter().map(Ok);
+        Ok::<_, TwilioErr>(Some((stream::iter(items), next)))
+    })
+    .try_flatten()
+    .take(paging.limit.unwrap_or(usize::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Paging;
+    use crate::{Transport, Twilio, TwilioResult};
+    use async_trait::async_trait;
+    use hyper::{Body, Request, Response};
+
+    #[derive(Debug)]
+    struct TwoPages;
+
+    #[async_trait]
+    impl Transport for TwoPages {
+        async fn send(&self, req: Request<Body>) -> TwilioResult<Response<Body>> {
+            let body = match req.uri().query() {
+                Some("PageSize=2") => {
+                    r#"{"conferences": [{"sid": "CF00000000000000000000000000000001"},
+                    {"sid": "CF00000000000000000000000000000002"}], "end": 1,
+                    "next_page_uri": "/2010-04-01/Accounts/AC1/Conferences.json?Page=1&PageSize=2",
+                    "uri": "", "start": 0, "page": 0, "page_size": 2}"#
+                }
+                Some("Page=1&PageSize=2") => {
+                    r#"{"conferences": [{"sid": "CF00000000000000000000000000000003"}], "end": 2,
+                    "next_page_uri": null, "uri": "", "start": 2, "page": 1, "page_size": 2}"#
+                }
+                q => panic!("unexpected query {:?}", q),
+            };
+            let body = body.replace(
+                r#"{"sid""#,
+                r#"{"account_sid": "AC00000000000000000000000000000001",
+                "date_updated": "Tue, 31 Aug 2010 20:36:28 +0000", "friendly_name": "", "region": "us1", "status": "completed", "uri": "", "sid""#,
+            );
+            Ok(Response::new(Body::from(body)))
+        }
+    }
+
+    #[tokio::test]
+    async fn follows_next_page_uri() {
+        let twilio = Twilio::builder("AC1", "token")
+            .transport(TwoPages)
+            .build()
+            .unwrap();
+        let all = twilio
+            .conferences()
+            .page_size(2)
+            .collect_all()
+            .await
+            .unwrap();
+        let sids = all.iter().map(|c| c.sid.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            sids,
+            [
+                "CF00000000000000000000000000000001",
+                "CF00000000000000000000000000000002",
+                "CF00000000000000000000000000000003"
+            ]
+        );
+
+        let limited = twilio.conferences().page_size(5).limit(2);
+        let all = limited.collect_all().await.unwrap();
+        assert_eq!(all.len(), 2);
+    }
+
+    #[test]
+    fn clamps_page_size() {
+        let paging = |page_size, limit| Paging { page_size, limit };
+        assert_eq!(paging(Some(5000), None).url("x".into()), "x?PageSize=1000");
+        assert_eq!(
+            paging(Some(50), Some(10)).url("x?a=b".into()),
+            "x?a=b&PageSize=10"
+        );
+        assert_eq!(paging(None, Some(10)).url("x".into()), "x");
+    }
+}
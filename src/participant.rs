@@ -0,0 +1,427 @@
+use super::{
+    bool_str, encode_pairs, AccountSid, Address, CallSid, ConferenceSid, DateTime, Execute,
+    GetConference, Page, Paging, Twilio, TwilioErr, TwilioRequest, TwilioResp,
+};
+use crate::phone::{self, IntoAddress, ParsePhoneError};
+use async_trait::async_trait;
+use hyper::{self, Method};
+use serde::Deserialize;
+use std::fmt;
+
+impl<'a> GetConference<'a> {
+    /// Everyone currently in the conference
+    pub fn participants(&self) -> Participants<'a> {
+        Participants {
+            conference_sid: self.conference.sid(),
+            client: self.client,
+            paging: Paging::default(),
+        }
+    }
+
+    /// The participant connected through the call `call_sid`
+    pub fn participant(&self, call_sid: &'a CallSid) -> GetParticipant<'a> {
+        GetParticipant {
+            conference_sid: self.conference.sid(),
+            call_sid,
+            client: self.client,
+        }
+    }
+
+    /// Dial `to` from `from` and put them in the conference once they answer
+    pub fn add_participant(
+        &self,
+        from: impl IntoAddress,
+        to: impl IntoAddress,
+    ) -> AddParticipant<'a> {
+        AddParticipant {
+            conference_sid: self.conference.sid(),
+            participant: Participant::new(from, to),
+            client: self.client,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Beep {
+    Always,
+    Never,
+    OnEnter,
+    OnExit,
+}
+
+impl Beep {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            Beep::Always => "true",
+            Beep::Never => "false",
+            Beep::OnEnter => "onEnter",
+            Beep::OnExit => "onExit",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Participant<'a> {
+    from: Address,
+    to: Address,
+    invalid: Option<ParsePhoneError>,
+    label: Option<&'a str>,
+    early_media: Option<bool>,
+    beep: Option<Beep>,
+    muted: Option<bool>,
+    record: Option<bool>,
+    start_conference_on_enter: Option<bool>,
+    end_conference_on_exit: Option<bool>,
+    timeout: Option<&'a str>,
+    wait_url: Option<&'a str>,
+    status_callback: Option<&'a str>,
+    call_sid_to_coach: Option<&'a CallSid>,
+}
+
+impl<'a> Participant<'a> {
+    pub fn new(from: impl IntoAddress, to: impl IntoAddress) -> Participant<'a> {
+        let mut invalid = None;
+        Participant {
+            from: phone::checked(from, &mut invalid),
+            to: phone::checked(to, &mut invalid),
+            invalid,
+            label: None,
+            early_media: None,
+            beep: None,
+            muted: None,
+            record: None,
+            start_conference_on_enter: None,
+            end_conference_on_exit: None,
+            timeout: None,
+            wait_url: None,
+            status_callback: None,
+            call_sid_to_coach: None,
+        }
+    }
+}
+
+impl<'a> fmt::Display for Participant<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (from, to) = (self.from.to_string(), self.to.to_string());
+        let mut pairs = vec![("From", from.as_str()), ("To", to.as_str())];
+        pair!(self, label, "Label", pairs);
+        pair!(self, timeout, "Timeout", pairs);
+        pair!(self, wait_url, "WaitUrl", pairs);
+        pair!(self, status_callback, "StatusCallback", pairs);
+        if let Some(sid) = self.call_sid_to_coach {
+            pairs.push(("Coaching", "true"));
+            pairs.push(("CallSidToCoach", sid.as_str()));
+        }
+        if let Some(beep) = self.beep {
+            pairs.push(("Beep", beep.as_str()));
+        }
+        let flags = [
+            ("EarlyMedia", self.early_media),
+            ("Muted", self.muted),
+            ("Record", self.record),
+            ("StartConferenceOnEnter", self.start_conference_on_enter),
+            ("EndConferenceOnExit", self.end_conference_on_exit),
+        ];
+        for (name, flag) in flags.iter() {
+            if let Some(flag) = flag {
+                pairs.push((name, bool_str(*flag)));
+            }
+        }
+
+        f.write_str(&encode_pairs(pairs).unwrap())
+    }
+}
+
+// ADD A PARTICIPANT
+#[derive(Debug)]
+pub struct AddParticipant<'a> {
+    pub conference_sid: &'a ConferenceSid,
+    pub participant: Participant<'a>,
+    pub client: &'a Twilio,
+}
+
+impl<'a> AddParticipant<'a> {
+    pub fn label(mut self, label: &'a str) -> AddParticipant<'a> {
+        self.participant.label = Some(label);
+        self
+    }
+
+    /// let the caller hear ringing before the participant answers
+    pub fn early_media(mut self, early_media: bool) -> AddParticipant<'a> {
+        self.participant.early_media = Some(early_media);
+        self
+    }
+
+    pub fn beep(mut self, beep: Beep) -> AddParticipant<'a> {
+        self.participant.beep = Some(beep);
+        self
+    }
+
+    pub fn muted(mut self, muted: bool) -> AddParticipant<'a> {
+        self.participant.muted = Some(muted);
+        self
+    }
+
+    pub fn record(mut self, record: bool) -> AddParticipant<'a> {
+        self.participant.record = Some(record);
+        self
+    }
+
+    pub fn start_conference_on_enter(mut self, start: bool) -> AddParticipant<'a> {
+        self.participant.start_conference_on_enter = Some(start);
+        self
+    }
+
+    pub fn end_conference_on_exit(mut self, end: bool) -> AddParticipant<'a> {
+        self.participant.end_conference_on_exit = Some(end);
+        self
+    }
+
+    /// seconds to let the participant's phone ring
+    pub fn timeout(mut self, timeout: &'a str) -> AddParticipant<'a> {
+        self.participant.timeout = Some(timeout);
+        self
+    }
+
+    pub fn wait_url(mut self, wait_url: &'a str) -> AddParticipant<'a> {
+        self.participant.wait_url = Some(wait_url);
+        self
+    }
+
+    pub fn status_callback(mut self, callback: &'a str) -> AddParticipant<'a> {
+        self.participant.status_callback = Some(callback);
+        self
+    }
+
+    /// join as a coach, only heard by the participant on `call_sid`
+    pub fn coach(mut self, call_sid: &'a CallSid) -> AddParticipant<'a> {
+        self.participant.call_sid_to_coach = Some(call_sid);
+        self
+    }
+}
+
+execute!(AddParticipant);
+
+#[async_trait]
+impl<'a> TwilioRequest for AddParticipant<'a> {
+    type Resp = ParticipantResp;
+
+    async fn run(&self) -> TwilioResp<Self::Resp> {
+        if let Some(ref e) = self.participant.invalid {
+            return Err(TwilioErr::PhoneErr(e.clone()));
+        }
+        let url = format!("Conferences/{}/Participants.json", self.conference_sid);
+        let participant = self.participant.to_string();
+        self.execute(Method::POST, url, Some(participant)).await
+    }
+}
+
+// GET ONE PARTICIPANT
+#[derive(Debug)]
+pub struct GetParticipant<'a> {
+    pub conference_sid: &'a ConferenceSid,
+    pub call_sid: &'a CallSid,
+    pub client: &'a Twilio,
+}
+
+execute!(GetParticipant);
+
+#[async_trait]
+impl<'a> TwilioRequest for GetParticipant<'a> {
+    type Resp = ParticipantResp;
+
+    async fn run(&self) -> TwilioResp<Self::Resp> {
+        self.execute(Method::GET, self.url(), None).await
+    }
+}
+
+impl<'a> GetParticipant<'a> {
+    fn url(&self) -> String {
+        format!(
+            "Conferences/{}/Participants/{}.json",
+            self.conference_sid, self.call_sid
+        )
+    }
+
+    async fn update(&self, pairs: &[(&str, &str)]) -> TwilioResp<ParticipantResp> {
+        self.execute(Method::POST, self.url(), encode_pairs(pairs))
+            .await
+    }
+
+    pub async fn mute(&self) -> TwilioResp<ParticipantResp> {
+        self.update(&[("Muted", "true")]).await
+    }
+
+    pub async fn unmute(&self) -> TwilioResp<ParticipantResp> {
+        self.update(&[("Muted", "false")]).await
+    }
+
+    /// Put the participant on hold, playing `hold_url` (Twilio's hold music
+    /// when `None`)
+    pub async fn hold(&self, hold_url: Option<&str>) -> TwilioResp<ParticipantResp> {
+        match hold_url {
+            Some(url) => self.update(&[("Hold", "true"), ("HoldUrl", url)]).await,
+            None => self.update(&[("Hold", "true")]).await,
+        }
+    }
+
+    pub async fn unhold(&self) -> TwilioResp<ParticipantResp> {
+        self.update(&[("Hold", "false")]).await
+    }
+
+    /// Play the TwiML at `url` to this participant only
+    pub async fn announce_url(&self, url: &str) -> TwilioResp<ParticipantResp> {
+        self.update(&[("AnnounceUrl", url)]).await
+    }
+
+    /// Make this participant a coach that only `call_sid` can hear
+    pub async fn coach(&self, call_sid: &CallSid) -> TwilioResp<ParticipantResp> {
+        self.update(&[("Coaching", "true"), ("CallSidToCoach", call_sid.as_str())])
+            .await
+    }
+
+    /// Remove the participant from the conference, hanging up their call
+    pub async fn kick(&self) -> TwilioResp<()> {
+        self.execute(Method::DELETE, self.url(), None).await
+    }
+}
+
+// GET ALL PARTICIPANTS
+#[derive(Debug)]
+pub struct Participants<'a> {
+    pub conference_sid: &'a ConferenceSid,
+    pub client: &'a Twilio,
+    pub paging: Paging,
+}
+
+impl<'a> Participants<'a> {
+    fn list_url(&self) -> String {
+        format!("Conferences/{}/Participants.json", self.conference_sid)
+    }
+}
+
+execute!(Participants);
+paginate!(Participants, ListParticipantsResp);
+
+#[async_trait]
+impl<'a> TwilioRequest for Participants<'a> {
+    type Resp = ListParticipantsResp;
+
+    async fn run(&self) -> TwilioResp<Self::Resp> {
+        let url = self.paging.url(self.list_url());
+        self.execute(Method::GET, url, None).await
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ListParticipantsResp {
+    pub participants: Vec<ParticipantResp>,
+    pub page: usize,
+    pub page_size: usize,
+    pub start: usize,
+    pub end: usize,
+    pub uri: String,
+    pub next_page_uri: Option<String>,
+    pub previous_page_uri: Option<String>,
+}
+
+impl Page for ListParticipantsResp {
+    type Item = ParticipantResp;
+
+    fn next_page_uri(&self) -> Option<&str> {
+        self.next_page_uri.as_deref()
+    }
+
+    fn into_items(self) -> Vec<ParticipantResp> {
+        self.participants
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ParticipantResp {
+    pub account_sid: AccountSid,
+    pub call_sid: CallSid,
+    pub conference_sid: ConferenceSid,
+    pub label: Option<String>,
+    pub call_sid_to_coach: Option<CallSid>,
+    pub coaching: bool,
+    pub muted: bool,
+    pub hold: bool,
+    pub start_conference_on_enter: bool,
+    pub end_conference_on_exit: bool,
+    pub status: ParticipantStatus,
+    #[serde(default, deserialize_with = "crate::datetime::deserialize_opt")]
+    pub date_created: Option<DateTime>,
+    #[serde(default, deserialize_with = "crate::datetime::deserialize_opt")]
+    pub date_updated: Option<DateTime>,
+    pub uri: String,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub enum ParticipantStatus {
+    queued,
+    connecting,
+    ringing,
+    connected,
+    complete,
+    failed,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Beep;
+    use crate::transport::Canned;
+    use crate::TwilioRequest;
+
+    const PARTICIPANT: &str = r#"{"account_sid": "AC00000000000000000000000000000001",
+        "call_sid": "CA00000000000000000000000000000001",
+        "conference_sid": "CF00000000000000000000000000000001", "label": null,
+        "call_sid_to_coach": null, "coaching": false, "muted": true, "hold": false,
+        "start_conference_on_enter": true, "end_conference_on_exit": false,
+        "status": "connected", "date_created": null, "date_updated": null, "uri": "/uri"}"#;
+
+    #[tokio::test]
+    async fn encodes_updates() {
+        let canned = Canned::new(200, PARTICIPANT);
+        let twilio = canned.client();
+        let conf_sid = "CF00000000000000000000000000000001".parse().unwrap();
+        let call_sid = "CA00000000000000000000000000000001".parse().unwrap();
+        let coach_sid = "CA00000000000000000000000000000002".parse().unwrap();
+        let conf = twilio.conference(&conf_sid);
+
+        conf.add_participant("+15005550006", "+14155550100")
+            .beep(Beep::OnEnter)
+            .coach(&coach_sid)
+            .muted(false)
+            .run()
+            .await
+            .unwrap();
+        let participant = conf.participant(&call_sid);
+        participant.mute().await.unwrap();
+        participant
+            .hold(Some("https://example.com/hold"))
+            .await
+            .unwrap();
+        participant.hold(None).await.unwrap();
+        participant.coach(&coach_sid).await.unwrap();
+
+        let url = "https://api.twilio.com/2010-04-01/Accounts/AC1/Conferences/\
+                   CF00000000000000000000000000000001/Participants";
+        assert_eq!(canned.seen()[0], format!("POST {}.json", url));
+        assert!(canned.seen()[1..]
+            .iter()
+            .all(|s| *s == format!("POST {}/CA00000000000000000000000000000001.json", url)));
+        assert_eq!(
+            canned.bodies(),
+            [
+                "From=%2B15005550006&To=%2B14155550100&Coaching=true\
+                 &CallSidToCoach=CA00000000000000000000000000000002&Beep=onEnter&Muted=false",
+                "Muted=true",
+                "Hold=true&HoldUrl=https%3A%2F%2Fexample.com%2Fhold",
+                "Hold=true",
+                "Coaching=true&CallSidToCoach=CA00000000000000000000000000000002",
+            ]
+        );
+    }
+}
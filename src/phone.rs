@@ -0,0 +1,337 @@
+//! Phone numbers and the other addresses Twilio can send to or from.
+//!
+//! `PhoneNumber` only checks the shape of a number (digits, E.164 length and
+//! the trunk prefix of its region), not each country's numbering plan.
+use std::{error::Error, fmt, str::FromStr};
+
+/// A number in E.164 format, e.g. `+14155550100`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PhoneNumber(String);
+
+/// How national numbers are written in a country
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    pub calling_code: u16,
+    /// dialed before national numbers and dropped in E.164, `0` in most of
+    /// Europe, `1` in North America
+    pub trunk_prefix: Option<&'static str>,
+    /// dialed before a country code to call abroad, `00` in most of the
+    /// world, `011` in North America
+    pub international_prefix: &'static str,
+}
+
+impl Region {
+    pub const US: Region = Region::new(1, Some("1"), "011");
+    pub const CA: Region = Region::new(1, Some("1"), "011");
+    pub const GB: Region = Region::new(44, Some("0"), "00");
+    pub const IE: Region = Region::new(353, Some("0"), "00");
+    pub const FR: Region = Region::new(33, Some("0"), "00");
+    pub const DE: Region = Region::new(49, Some("0"), "00");
+    pub const ES: Region = Region::new(34, None, "00");
+    pub const IT: Region = Region::new(39, None, "00");
+    pub const NL: Region = Region::new(31, Some("0"), "00");
+    pub const AU: Region = Region::new(61, Some("0"), "0011");
+    pub const NZ: Region = Region::new(64, Some("0"), "00");
+    pub const IN: Region = Region::new(91, Some("0"), "00");
+    pub const BR: Region = Region::new(55, Some("0"), "00");
+    pub const MX: Region = Region::new(52, None, "00");
+    pub const JP: Region = Region::new(81, Some("0"), "010");
+
+    pub const fn new(
+        calling_code: u16,
+        trunk_prefix: Option<&'static str>,
+        international_prefix: &'static str,
+    ) -> Region {
+        Region {
+            calling_code,
+            trunk_prefix,
+            international_prefix,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParsePhoneError {
+    /// something other than digits and the usual separators
+    InvalidChar(char),
+    /// E.164 numbers are between 7 and 15 digits long
+    Length(usize),
+    /// country calling codes never start with `0`
+    InvalidCountryCode,
+    /// a national number was given without a region to read it in
+    NotInternational(String),
+    /// `client:` or `sip:` with nothing after it
+    Empty,
+}
+
+impl Error for ParsePhoneError {}
+
+impl fmt::Display for ParsePhoneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ParsePhoneError::InvalidChar(c) => write!(f, "Invalid character {:?} in number", c),
+            ParsePhoneError::Length(n) => {
+                write!(f, "Number has {} digits, E.164 allows 7 to 15", n)
+            }
+            ParsePhoneError::InvalidCountryCode => {
+                write!(f, "Country calling codes can't start with 0")
+            }
+            ParsePhoneError::NotInternational(ref n) => {
+                write!(f, "{:?} has no country code and no region was given", n)
+            }
+            ParsePhoneError::Empty => write!(f, "Address is empty"),
+        }
+    }
+}
+
+impl PhoneNumber {
+    /// Parse `number`, reading it as a national number of `region` unless it
+    /// starts with `+` or the international prefix of `region`.
+    pub fn parse(number: &str, region: Region) -> Result<PhoneNumber, ParsePhoneError> {
+        PhoneNumber::parse_in(number, Some(region))
+    }
+
+    fn parse_in(number: &str, region: Option<Region>) -> Result<PhoneNumber, ParsePhoneError> {
+        let trimmed = number.trim();
+        let (international, rest) = match trimmed.strip_prefix('+') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed),
+        };
+        let mut digits = String::with_capacity(rest.len());
+        for c in rest.chars() {
+            match c {
+                '0'..='9' => digits.push(c),
+                ' ' | '-' | '.' | '(' | ')' | '/' => {}
+                c => return Err(ParsePhoneError::InvalidChar(c)),
+            }
+        }
+        let intl_prefix = region.map_or("00", |r| r.international_prefix);
+        let e164 = match (international, digits.strip_prefix(intl_prefix), region) {
+            (true, _, _) => digits,
+            (false, Some(intl), _) => intl.to_owned(),
+            (false, None, Some(region)) => {
+                let national = match region.trunk_prefix {
+                    Some(trunk) => digits.strip_prefix(trunk).unwrap_or(&digits),
+                    None => &digits,
+                };
+                format!("{}{}", region.calling_code, national)
+            }
+            (false, None, None) => {
+                return Err(ParsePhoneError::NotInternational(number.to_owned()))
+            }
+        };
+        if e164.starts_with('0') {
+            return Err(ParsePhoneError::InvalidCountryCode);
+        }
+        if !(7..=15).contains(&e164.len()) {
+            return Err(ParsePhoneError::Length(e164.len()));
+        }
+        Ok(PhoneNumber(format!("+{}", e164)))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// only accepts international numbers, use `PhoneNumber::parse` for national
+/// ones
+impl FromStr for PhoneNumber {
+    type Err = ParsePhoneError;
+
+    fn from_str(number: &str) -> Result<Self, Self::Err> {
+        PhoneNumber::parse_in(number, None)
+    }
+}
+
+impl fmt::Display for PhoneNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Anything Twilio accepts as the `To` or `From` of a message or call
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Address {
+    Phone(PhoneNumber),
+    WhatsApp(PhoneNumber),
+    /// a Twilio Client identity, sent as `client:{identity}`
+    Client(String),
+    /// a SIP uri, sent as `sip:{uri}`
+    Sip(String),
+    /// sent as is without any checks: short codes and alphanumeric sender ids
+    Raw(String),
+}
+
+impl Address {
+    /// Parse a `client:`, `sip:` or `whatsapp:` address, or a phone number
+    /// read in `region` when it has no country code
+    pub fn parse(address: &str, region: Region) -> Result<Address, ParsePhoneError> {
+        Address::parse_in(address, Some(region))
+    }
+
+    fn parse_in(address: &str, region: Option<Region>) -> Result<Address, ParsePhoneError> {
+        let address = address.trim();
+        let non_empty = |s: &str| match s {
+            "" => Err(ParsePhoneError::Empty),
+            s => Ok(s.to_owned()),
+        };
+        if let Some(identity) = address.strip_prefix("client:") {
+            Ok(Address::Client(non_empty(identity)?))
+        } else if let Some(uri) = address.strip_prefix("sip:") {
+            Ok(Address::Sip(non_empty(uri)?))
+        } else if let Some(number) = address.strip_prefix("whatsapp:") {
+            Ok(Address::WhatsApp(PhoneNumber::parse_in(number, region)?))
+        } else {
+            Ok(Address::Phone(PhoneNumber::parse_in(address, region)?))
+        }
+    }
+}
+
+/// phone numbers must be international, use `Address::parse` for national ones
+impl FromStr for Address {
+    type Err = ParsePhoneError;
+
+    fn from_str(address: &str) -> Result<Self, Self::Err> {
+        Address::parse_in(address, None)
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Address::Phone(ref number) => write!(f, "{}", number),
+            Address::WhatsApp(ref number) => write!(f, "whatsapp:{}", number),
+            Address::Client(ref identity) => write!(f, "client:{}", identity),
+            Address::Sip(ref uri) => write!(f, "sip:{}", uri),
+            Address::Raw(ref raw) => f.write_str(raw),
+        }
+    }
+}
+
+impl From<PhoneNumber> for Address {
+    fn from(number: PhoneNumber) -> Address {
+        Address::Phone(number)
+    }
+}
+
+impl From<&PhoneNumber> for Address {
+    fn from(number: &PhoneNumber) -> Address {
+        Address::Phone(number.clone())
+    }
+}
+
+/// Conversion into the `To` or `From` of a message, call or participant.
+///
+/// Strings starting with `+`, `client:`, `sip:` or `whatsapp:` are parsed and
+/// rejected by `run()` when they are malformed, anything else (short codes,
+/// alphanumeric sender ids) is sent as an `Address::Raw`.
+pub trait IntoAddress {
+    fn into_address(self) -> Result<Address, ParsePhoneError>;
+}
+
+impl IntoAddress for Address {
+    fn into_address(self) -> Result<Address, ParsePhoneError> {
+        Ok(self)
+    }
+}
+
+impl IntoAddress for PhoneNumber {
+    fn into_address(self) -> Result<Address, ParsePhoneError> {
+        Ok(self.into())
+    }
+}
+
+impl IntoAddress for &PhoneNumber {
+    fn into_address(self) -> Result<Address, ParsePhoneError> {
+        Ok(self.into())
+    }
+}
+
+impl IntoAddress for &str {
+    fn into_address(self) -> Result<Address, ParsePhoneError> {
+        let trimmed = self.trim();
+        if ["+", "client:", "sip:", "whatsapp:"]
+            .iter()
+            .any(|prefix| trimmed.starts_with(prefix))
+        {
+            trimmed.parse()
+        } else {
+            Ok(Address::Raw(self.to_owned()))
+        }
+    }
+}
+
+impl IntoAddress for &String {
+    fn into_address(self) -> Result<Address, ParsePhoneError> {
+        self.as_str().into_address()
+    }
+}
+
+impl IntoAddress for String {
+    fn into_address(self) -> Result<Address, ParsePhoneError> {
+        self.as_str().into_address()
+    }
+}
+
+/// Converts `addr`, keeping the first parse error in `invalid` so the request
+/// can report it from `run()` instead of sending
+pub(crate) fn checked(addr: impl IntoAddress, invalid: &mut Option<ParsePhoneError>) -> Address {
+    addr.into_address().unwrap_or_else(|e| {
+        invalid.get_or_insert(e);
+        Address::Raw(String::new())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_national_numbers() {
+        let parse = |n, region| PhoneNumber::parse(n, region).unwrap().to_string();
+        assert_eq!(parse("(415) 555-0100", Region::US), "+14155550100");
+        assert_eq!(parse("1 415 555 0100", Region::US), "+14155550100");
+        assert_eq!(parse("020 7946 0018", Region::GB), "+442079460018");
+        assert_eq!(parse("+44 20 7946 0018", Region::US), "+442079460018");
+        assert_eq!(parse("0044 20 7946 0018", Region::DE), "+442079460018");
+        assert_eq!(parse("011 44 20 7946 0018", Region::US), "+442079460018");
+        assert_eq!(parse("0011 44 20 7946 0018", Region::AU), "+442079460018");
+        assert_eq!(parse("010 44 20 7946 0018", Region::JP), "+442079460018");
+        assert_eq!(parse("06 1234 5678", Region::IT), "+390612345678");
+
+        assert_eq!(
+            "415 555 0100".parse::<PhoneNumber>(),
+            Err(ParsePhoneError::NotInternational("415 555 0100".into()))
+        );
+        assert_eq!(
+            "+1 415 555 01OO".parse::<PhoneNumber>(),
+            Err(ParsePhoneError::InvalidChar('O'))
+        );
+        assert_eq!(
+            "+1234567890123456".parse::<PhoneNumber>(),
+            Err(ParsePhoneError::Length(16))
+        );
+        assert_eq!(
+            "+044 20 7946 0018".parse::<PhoneNumber>(),
+            Err(ParsePhoneError::InvalidCountryCode)
+        );
+    }
+
+    #[test]
+    fn parses_addresses() {
+        let parse = |a| Address::parse(a, Region::GB).unwrap();
+        assert_eq!(parse("client:alice").to_string(), "client:alice");
+        assert_eq!(
+            parse("sip:bob@example.com").to_string(),
+            "sip:bob@example.com"
+        );
+        assert_eq!(
+            parse("whatsapp:07700 900123").to_string(),
+            "whatsapp:+447700900123"
+        );
+        assert_eq!("client:".parse::<Address>(), Err(ParsePhoneError::Empty));
+        assert_eq!("MyBrand".into_address(), Ok(Address::Raw("MyBrand".into())));
+        assert_eq!("+1 415 55".into_address(), Err(ParsePhoneError::Length(6)));
+    }
+}
@@ -0,0 +1,77 @@
+//! Prices as exact decimals, enabled by the `rust_decimal` feature.
+use rust_decimal::Decimal;
+use serde::{de, Deserialize, Deserializer};
+use std::{fmt, str::FromStr};
+
+/// What Twilio charged for a message, call, recording or transcription.
+///
+/// Twilio reports charges as negative amounts, e.g. `-0.00750`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Price {
+    pub amount: Decimal,
+    pub currency: Currency,
+}
+
+/// ISO 4217 currency code, e.g. `USD`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Currency(String);
+
+impl Currency {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl fmt::Display for Price {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.amount, self.currency)
+    }
+}
+
+/// builds a `Price` out of the flattened `price` and `price_unit` fields,
+/// `None` until Twilio has billed the resource
+pub(crate) fn deserialize<'de, D>(de: D) -> Result<Option<Price>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Raw {
+        price: Option<String>,
+        price_unit: Option<String>,
+    }
+    let raw = Raw::deserialize(de)?;
+    match (raw.price, raw.price_unit) {
+        (Some(price), Some(unit)) => Ok(Some(Price {
+            amount: Decimal::from_str(&price).map_err(de::Error::custom)?,
+            currency: Currency(unit.to_uppercase()),
+        })),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::MsgResp;
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn parses_negative_price() {
+        let msg: MsgResp = serde_json::from_str(
+            r#"{"from": "+15005550006", "to": "+14155550100", "body": "hi",
+            "sid": "SM00000000000000000000000000000001", "status": "delivered", "num_media": "0", "price": "-0.00750", "price_unit": "usd",
+            "uri": "/uri", "date_created": "Tue, 31 Aug 2010 20:36:28 +0000",
+            "date_sent": null, "date_updated": "Tue, 31 Aug 2010 20:36:28 +0000"}"#,
+        )
+        .unwrap();
+        let price = msg.price.unwrap();
+        assert_eq!(price.amount, Decimal::new(-750, 5));
+        assert_eq!(price.currency.as_str(), "USD");
+        assert_eq!(price.to_string(), "-0.00750 USD");
+    }
+}
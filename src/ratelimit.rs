@@ -0,0 +1,167 @@
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+use tokio::time::Instant;
+
+/// Kind of sender a message goes out from, Twilio throttles each one
+/// differently
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SenderType {
+    LongCode,
+    TollFree,
+    ShortCode,
+    MessagingService,
+}
+
+// north american toll-free area codes
+const TOLL_FREE: [&str; 7] = ["800", "833", "844", "855", "866", "877", "888"];
+
+impl SenderType {
+    /// Best guess at the sender type from a `From` number or messaging
+    /// service sid
+    pub fn of(sender: &str) -> SenderType {
+        let digits = sender.trim_start_matches('+');
+        if sender.starts_with("MG") {
+            SenderType::MessagingService
+        } else if (5..=6).contains(&digits.len()) && digits.chars().all(|c| c.is_ascii_digit()) {
+            SenderType::ShortCode
+        } else if digits.len() == 11
+            && digits.starts_with('1')
+            && TOLL_FREE.contains(&&digits[1..4])
+        {
+            SenderType::TollFree
+        } else {
+            SenderType::LongCode
+        }
+    }
+
+    /// messages per second Twilio allows for this sender type by default
+    fn default_rate(self) -> f64 {
+        match self {
+            SenderType::LongCode => 1.,
+            SenderType::TollFree => 3.,
+            SenderType::ShortCode => 100.,
+            SenderType::MessagingService => 1.,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    rate: f64,
+    last: Instant,
+}
+
+impl Bucket {
+    fn new(rate: f64) -> Bucket {
+        // a zero or negative rate would never refill
+        let rate = rate.max(0.001);
+        Bucket {
+            tokens: rate.max(1.),
+            rate,
+            last: Instant::now(),
+        }
+    }
+
+    /// take a token or return how long until the next one is available
+    fn take(&mut self) -> Result<(), Duration> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last).as_secs_f64();
+        // allow at most one second worth of burst
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate.max(1.));
+        self.last = now;
+        if self.tokens >= 1. {
+            self.tokens -= 1.;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1. - self.tokens) / self.rate))
+        }
+    }
+}
+
+/// Token bucket per `From` number or messaging service. Attach one to the
+/// client with
+/// [`TwilioBuilder::rate_limiter`](crate::TwilioBuilder::rate_limiter) or
+/// to a single message with [`SendMsg::rate_limiter`](crate::SendMsg::rate_limiter)
+/// and `run()` waits for a free slot instead of getting a 429 back.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    rates: HashMap<SenderType, f64>,
+    senders: HashMap<String, f64>,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    /// Twilio's default throughput: 1 mps for long codes and messaging
+    /// services, 3 mps for toll-free numbers and 100 mps for short codes
+    pub fn new() -> RateLimiter {
+        RateLimiter::default()
+    }
+
+    /// messages per second for every sender of type `ty`
+    pub fn rate(mut self, ty: SenderType, per_second: f64) -> RateLimiter {
+        self.rates.insert(ty, per_second);
+        self
+    }
+
+    /// messages per second for one particular sender, wins over `rate`
+    pub fn sender_rate<S: Into<String>>(mut self, sender: S, per_second: f64) -> RateLimiter {
+        self.senders.insert(sender.into(), per_second);
+        self
+    }
+
+    fn rate_for(&self, sender: &str) -> f64 {
+        if let Some(&rate) = self.senders.get(sender) {
+            return rate;
+        }
+        let ty = SenderType::of(sender);
+        self.rates
+            .get(&ty)
+            .copied()
+            .unwrap_or_else(|| ty.default_rate())
+    }
+
+    /// Wait until `sender` may send another message
+    pub async fn acquire(&self, sender: &str) {
+        loop {
+            let wait = {
+                let mut buckets = self.buckets.lock().unwrap();
+                let bucket = buckets
+                    .entry(sender.to_owned())
+                    .or_insert_with(|| Bucket::new(self.rate_for(sender)));
+                match bucket.take() {
+                    Ok(()) => return,
+                    Err(wait) => wait,
+                }
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sender_type() {
+        assert_eq!(SenderType::of("+18005550100"), SenderType::TollFree);
+        assert_eq!(SenderType::of("+15005550006"), SenderType::LongCode);
+        assert_eq!(SenderType::of("+447700900123"), SenderType::LongCode);
+        assert_eq!(SenderType::of("12345"), SenderType::ShortCode);
+        assert_eq!(
+            SenderType::of("MG9752274e9e519418a7406176694466fa"),
+            SenderType::MessagingService
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn throttles() {
+        let limiter = RateLimiter::new().rate(SenderType::LongCode, 20.);
+        let start = Instant::now();
+        for _ in 0..25 {
+            limiter.acquire("+15005550006").await;
+        }
+        // 20 go out as a burst, the next 5 have to wait 50ms each
+        assert_eq!(start.elapsed(), Duration::from_millis(250));
+    }
+}
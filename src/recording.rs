@@ -1,17 +1,31 @@
-use super::{Execute, Twilio, TwilioErr, TwilioJson, TwilioRequest, TwilioResp};
+use super::{
+    encode_pairs, AccountSid, ApiError, CallSid, ConferenceSid, DateTime, Execute, FilterDate,
+    GetCall, GetConference, Page, Paging, RecordingSid, TimeFilter, Twilio, TwilioErr,
+    TwilioRequest, TwilioResp, Url,
+};
+#[cfg(feature = "rust_decimal")]
+use crate::Price;
 use async_trait::async_trait;
-use hyper::{self, Method};
+use bytes::Bytes;
+use futures::{Stream, TryStreamExt};
+use hyper::{self, header::LOCATION, Body, Method, Request, Response};
 use serde::Deserialize;
+use std::fmt;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct Recording<'a> {
-    sid: &'a str,
+    sid: &'a RecordingSid,
 }
 
 impl<'a> Recording<'a> {
-    pub fn new(sid: &'a str) -> Recording<'a> {
+    pub fn new(sid: &'a RecordingSid) -> Recording<'a> {
         Recording { sid }
     }
+
+    pub(crate) fn sid(&self) -> &'a RecordingSid {
+        self.sid
+    }
 }
 
 // GET ONE Recording
@@ -27,60 +41,198 @@ execute!(GetRecording);
 impl<'a> TwilioRequest for GetRecording<'a> {
     type Resp = RecordingResp;
 
-    async fn run(&self) -> TwilioResp<TwilioJson<Self::Resp>> {
+    async fn run(&self) -> TwilioResp<Self::Resp> {
         let url = format!("Recordings/{}.json", self.recording.sid);
         self.execute(Method::GET, url, None).await
     }
 }
 
 impl<'a> GetRecording<'a> {
-    pub async fn delete(&self) -> TwilioResp<TwilioJson<Option<bool>>> {
+    pub async fn delete(&self) -> TwilioResp<()> {
         let url = format!("Recordings/{}.json", self.recording.sid);
         self.execute(Method::DELETE, url, None).await
     }
+
+    /// The recording's audio instead of its metadata
+    pub fn download(&self, format: RecordingFormat) -> DownloadRecording<'a> {
+        DownloadRecording {
+            sid: self.recording.sid(),
+            format,
+            requested_channels: None,
+            client: self.client,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordingFormat {
+    Wav,
+    Mp3,
+}
+
+// DOWNLOAD A RECORDING
+#[derive(Debug)]
+pub struct DownloadRecording<'a> {
+    pub sid: &'a RecordingSid,
+    pub format: RecordingFormat,
+    pub requested_channels: Option<u8>,
+    pub client: &'a Twilio,
+}
+
+execute!(DownloadRecording);
+
+// media can be served from another host, don't follow redirects forever
+const MAX_REDIRECTS: usize = 5;
+
+impl<'a> DownloadRecording<'a> {
+    /// `2` gets both legs of a dual-channel recording as separate channels,
+    /// by default they are mixed down to mono
+    pub fn requested_channels(mut self, channels: u8) -> DownloadRecording<'a> {
+        self.requested_channels = Some(channels);
+        self
+    }
+
+    fn url(&self) -> String {
+        let ext = match self.format {
+            RecordingFormat::Wav => "wav",
+            RecordingFormat::Mp3 => "mp3",
+        };
+        match self.requested_channels {
+            Some(channels) => format!(
+                "Recordings/{}.{}?RequestedChannels={}",
+                self.sid, ext, channels
+            ),
+            None => format!("Recordings/{}.{}", self.sid, ext),
+        }
+    }
+
+    async fn response(&self) -> TwilioResp<Response<Body>> {
+        let path = self.url();
+        let mut res = crate::retry::send(self.client, true, None, || {
+            self.request(Method::GET, &path, None)
+        })
+        .await?;
+        let mut url = self.client.url(&path);
+        // the location is a pre-signed media url, the credentials stay here
+        for _ in 0..MAX_REDIRECTS {
+            // a relative location is resolved against the url that redirected,
+            // one that can't be is returned as the redirect's error below
+            let next = match res.headers().get(LOCATION) {
+                Some(location) if res.status().is_redirection() => location
+                    .to_str()
+                    .ok()
+                    .and_then(|location| Url::parse(&url).ok()?.join(location).ok()),
+                _ => None,
+            };
+            let next = match next {
+                Some(next) => next.to_string(),
+                None => break,
+            };
+            res = crate::retry::send(self.client, true, None, || {
+                Ok(Request::get(next.as_str()).body(Body::empty())?)
+            })
+            .await?;
+            url = next;
+        }
+        if !res.status().is_success() {
+            let status = res.status();
+            let body = hyper::body::to_bytes(res).await?;
+            return Err(ApiError::from_response(status, &body).into());
+        }
+        Ok(res)
+    }
+
+    /// Audio as it arrives, without buffering the whole file
+    pub async fn stream(&self) -> TwilioResp<impl Stream<Item = TwilioResp<Bytes>>> {
+        let res = self.response().await?;
+        Ok(res.into_body().map_err(TwilioErr::NetworkErr))
+    }
+
+    /// Copy the audio into `writer`, returns the number of bytes written
+    pub async fn write_to<W>(&self, writer: &mut W) -> TwilioResp<u64>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut body = self.stream().await?;
+        let mut written = 0;
+        while let Some(chunk) = body.try_next().await? {
+            writer.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        writer.flush().await?;
+        Ok(written)
+    }
 }
 
 // GET ALL RECORDINGS
 #[derive(Debug)]
 pub struct Recordings<'a> {
     pub client: &'a Twilio,
+    pub call_sid: Option<&'a CallSid>,
+    pub conference_sid: Option<&'a ConferenceSid>,
+    pub date_created: TimeFilter<'a>,
+    pub paging: Paging,
 }
 
 execute!(Recordings);
+paginate!(Recordings, ListRecordingResp);
 
 #[async_trait]
 impl<'a> TwilioRequest for Recordings<'a> {
     type Resp = ListRecordingResp;
 
-    async fn run(&self) -> TwilioResp<TwilioJson<Self::Resp>> {
-        self.execute(Method::GET, "Recordings.json", None).await
+    async fn run(&self) -> TwilioResp<Self::Resp> {
+        let url = self.paging.url(self.list_url());
+        self.execute(Method::GET, url, None).await
     }
 }
 
 impl<'a> Recordings<'a> {
-    pub async fn for_call(&self, call_sid: &'a str) -> TwilioResp<TwilioJson<ListRecordingResp>> {
-        let url = format!("Recordings.json?CallSid={}", call_sid);
-        self.execute(Method::GET, url, None).await
+    fn list_url(&self) -> String {
+        let mut pairs = Vec::new();
+        if let Some(sid) = self.call_sid {
+            pairs.push(("CallSid", sid.as_str()));
+        }
+        if let Some(sid) = self.conference_sid {
+            pairs.push(("ConferenceSid", sid.as_str()));
+        }
+        self.date_created
+            .pairs(["DateCreated", "DateCreated<", "DateCreated>"], &mut pairs);
+        match encode_pairs(pairs) {
+            Some(query) if !query.is_empty() => format!("Recordings.json?{}", query),
+            _ => "Recordings.json".into(),
+        }
     }
 
-    pub async fn created(
-        &self,
-        date_created: &'a str,
-    ) -> TwilioResp<TwilioJson<ListRecordingResp>> {
-        let url = format!("Recordings.json?DateCreated={}", date_created);
-        self.execute(Method::GET, url, None).await
+    pub fn for_call(mut self, call_sid: &'a CallSid) -> Recordings<'a> {
+        self.call_sid = Some(call_sid);
+        self
     }
 
-    pub async fn range(
-        &self,
-        before: &'a str,
-        after: &'a str,
-    ) -> TwilioResp<TwilioJson<ListRecordingResp>> {
-        let url = format!(
-            "Recordings.json?DateCreatedBefore={}&DateCreatedAfter={}",
-            before, after
-        );
-        self.execute(Method::GET, url, None).await
+    pub fn for_conference(mut self, conference_sid: &'a ConferenceSid) -> Recordings<'a> {
+        self.conference_sid = Some(conference_sid);
+        self
+    }
+
+    /// Only recordings created within `filter`, dates are `YYYY-MM-DD`.
+    /// Replaces any date filter set before, as does `created`
+    pub fn date_created(mut self, filter: TimeFilter<'a>) -> Recordings<'a> {
+        self.date_created = filter;
+        self
+    }
+
+    pub fn created(self, date_created: impl Into<FilterDate<'a>>) -> Recordings<'a> {
+        self.date_created(TimeFilter::on(date_created))
+    }
+
+    /// Takes `before` first, unlike `TimeFilter::between`
+    #[deprecated(note = "use `date_created(TimeFilter::between(after, before))`")]
+    pub fn range(
+        self,
+        before: impl Into<FilterDate<'a>>,
+        after: impl Into<FilterDate<'a>>,
+    ) -> Recordings<'a> {
+        self.date_created(TimeFilter::between(after, before))
     }
 }
 
@@ -88,31 +240,219 @@ impl<'a> Recordings<'a> {
 pub struct ListRecordingResp {
     pub recordings: Vec<RecordingResp>,
     pub end: usize,
-    pub account_sid: String,
+    pub account_sid: AccountSid,
     pub start: usize,
     pub page: usize,
     pub page_size: usize,
+    pub next_page_uri: Option<String>,
+    pub previous_page_uri: Option<String>,
+}
+
+impl Page for ListRecordingResp {
+    type Item = RecordingResp;
+
+    fn next_page_uri(&self) -> Option<&str> {
+        self.next_page_uri.as_deref()
+    }
+
+    fn into_items(self) -> Vec<RecordingResp> {
+        self.recordings
+    }
 }
 
+// recordings that are still in progress come back without most of their
+// details, hence all the options
 #[derive(Deserialize, Debug)]
 pub struct RecordingResp {
-    pub account_sid: String,
-    pub call_sid: String,
-    pub channels: String,
-    pub conference_sid: String,
-    pub date_created: String,
-    pub date_updated: String,
-    pub end_time: String,
-    pub start_time: String,
-    pub price: String,
-    pub price_unit: String,
-    pub duration: String,
-    pub encryption_details: EncryptionDetails,
-    pub error_code: String,
+    pub sid: RecordingSid,
+    pub account_sid: AccountSid,
+    pub call_sid: CallSid,
+    pub channels: u8,
+    pub conference_sid: Option<ConferenceSid>,
+    #[serde(default, deserialize_with = "crate::datetime::deserialize_opt")]
+    pub date_created: Option<DateTime>,
+    #[serde(default, deserialize_with = "crate::datetime::deserialize_opt")]
+    pub date_updated: Option<DateTime>,
+    #[serde(default, deserialize_with = "crate::datetime::deserialize_opt")]
+    pub end_time: Option<DateTime>,
+    #[serde(default, deserialize_with = "crate::datetime::deserialize_opt")]
+    pub start_time: Option<DateTime>,
+    #[cfg(not(feature = "rust_decimal"))]
+    pub price: Option<String>,
+    #[cfg(not(feature = "rust_decimal"))]
+    pub price_unit: Option<String>,
+    #[cfg(feature = "rust_decimal")]
+    #[serde(flatten, deserialize_with = "crate::price::deserialize")]
+    pub price: Option<Price>,
+    /// seconds, `-1` until the recording is complete
+    pub duration: Option<String>,
+    pub encryption_details: Option<EncryptionDetails>,
+    pub error_code: Option<usize>,
     pub uri: String,
     pub status: RecordingStatus,
 }
 
+// START, PAUSE, RESUME & STOP RECORDING LIVE CALLS
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordingChannels {
+    Mono,
+    /// each leg of the call in its own channel
+    Dual,
+}
+
+/// What ends up in the recording while it is paused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseBehavior {
+    /// leave the paused part out
+    Skip,
+    /// keep the paused part as silence
+    Silence,
+}
+
+#[derive(Debug)]
+pub struct StartRecording<'a> {
+    pub call_sid: &'a CallSid,
+    pub channels: Option<RecordingChannels>,
+    pub status_callback: Option<&'a str>,
+    pub trim: Option<bool>,
+    pub client: &'a Twilio,
+}
+
+impl<'a> GetCall<'a> {
+    /// Start recording the call from now on
+    pub fn start_recording(&self) -> StartRecording<'a> {
+        StartRecording {
+            call_sid: self.call_sid,
+            channels: None,
+            status_callback: None,
+            trim: None,
+            client: self.client,
+        }
+    }
+
+    /// A recording of this call that is in progress or paused
+    pub fn recording(&self, sid: &'a RecordingSid) -> LiveRecording<'a> {
+        LiveRecording {
+            parent: format!("Calls/{}", self.call_sid),
+            sid,
+            client: self.client,
+        }
+    }
+}
+
+impl<'a> GetConference<'a> {
+    /// A recording of this conference that is in progress or paused.
+    ///
+    /// Twilio has no endpoint to start recording a running conference, use
+    /// `record` on [`AddParticipant`](crate::AddParticipant) or on `<Dial>`
+    /// instead.
+    pub fn recording(&self, sid: &'a RecordingSid) -> LiveRecording<'a> {
+        LiveRecording {
+            parent: format!("Conferences/{}", self.conference.sid()),
+            sid,
+            client: self.client,
+        }
+    }
+}
+
+impl<'a> StartRecording<'a> {
+    pub fn channels(mut self, channels: RecordingChannels) -> StartRecording<'a> {
+        self.channels = Some(channels);
+        self
+    }
+
+    pub fn status_callback(mut self, callback: &'a str) -> StartRecording<'a> {
+        self.status_callback = Some(callback);
+        self
+    }
+
+    /// trim leading and trailing silence
+    pub fn trim(mut self, trim: bool) -> StartRecording<'a> {
+        self.trim = Some(trim);
+        self
+    }
+}
+
+impl<'a> fmt::Display for StartRecording<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut pairs = Vec::new();
+        if let Some(channels) = self.channels {
+            let channels = match channels {
+                RecordingChannels::Mono => "mono",
+                RecordingChannels::Dual => "dual",
+            };
+            pairs.push(("RecordingChannels", channels));
+        }
+        pair!(self, status_callback, "RecordingStatusCallback", pairs);
+        if let Some(trim) = self.trim {
+            let trim = if trim { "trim-silence" } else { "do-not-trim" };
+            pairs.push(("Trim", trim));
+        }
+        f.write_str(&encode_pairs(pairs).unwrap())
+    }
+}
+
+execute!(StartRecording);
+
+#[async_trait]
+impl<'a> TwilioRequest for StartRecording<'a> {
+    type Resp = RecordingResp;
+
+    async fn run(&self) -> TwilioResp<Self::Resp> {
+        let url = format!("Calls/{}/Recordings.json", self.call_sid);
+        self.execute(Method::POST, url, Some(self.to_string()))
+            .await
+    }
+}
+
+#[derive(Debug)]
+pub struct LiveRecording<'a> {
+    /// `Calls/{sid}` or `Conferences/{sid}`
+    pub parent: String,
+    pub sid: &'a RecordingSid,
+    pub client: &'a Twilio,
+}
+
+execute!(LiveRecording);
+
+#[async_trait]
+impl<'a> TwilioRequest for LiveRecording<'a> {
+    type Resp = RecordingResp;
+
+    async fn run(&self) -> TwilioResp<Self::Resp> {
+        self.execute(Method::GET, self.url(), None).await
+    }
+}
+
+impl<'a> LiveRecording<'a> {
+    fn url(&self) -> String {
+        format!("{}/Recordings/{}.json", self.parent, self.sid)
+    }
+
+    async fn update(&self, pairs: &[(&str, &str)]) -> TwilioResp<RecordingResp> {
+        self.execute(Method::POST, self.url(), encode_pairs(pairs))
+            .await
+    }
+
+    pub async fn pause(&self, behavior: PauseBehavior) -> TwilioResp<RecordingResp> {
+        let behavior = match behavior {
+            PauseBehavior::Skip => "skip",
+            PauseBehavior::Silence => "silence",
+        };
+        self.update(&[("Status", "paused"), ("PauseBehavior", behavior)])
+            .await
+    }
+
+    pub async fn resume(&self) -> TwilioResp<RecordingResp> {
+        self.update(&[("Status", "in-progress")]).await
+    }
+
+    /// Stop for good, the recording can't be resumed afterwards
+    pub async fn stop(&self) -> TwilioResp<RecordingResp> {
+        self.update(&[("Status", "stopped")]).await
+    }
+}
+
 #[derive(Deserialize, Debug)]
 pub struct EncryptionDetails {
     pub encryption_public_key_sid: String,
@@ -120,7 +460,7 @@ pub struct EncryptionDetails {
     pub encryption_iv: String,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[allow(non_camel_case_types)]
 pub enum RecordingStatus {
     #[serde(rename = "in-progress")]
@@ -129,5 +469,134 @@ pub enum RecordingStatus {
     stopped,
     processing,
     completed,
+    absent,
     failed,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{PauseBehavior, RecordingChannels, RecordingFormat};
+    use crate::{transport::Canned, RetryPolicy, TimeFilter, Twilio, TwilioRequest};
+    use hyper::{header::LOCATION, Body, Response};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn follows_relative_redirect() {
+        let redirect = Response::builder()
+            .status(307)
+            .header(LOCATION, "/media/RE1.wav?Signature=abc")
+            .body(Body::empty())
+            .unwrap();
+        let unavailable = Response::builder().status(503).body(Body::empty()).unwrap();
+        let canned = Canned::scripted(vec![redirect, unavailable], "RIFF");
+        let twilio = Twilio::builder("AC1", "token")
+            .transport(canned.clone())
+            .retry_policy(RetryPolicy::exponential(2).base_delay(Duration::from_millis(1)))
+            .build()
+            .unwrap();
+
+        let sid = "RE00000000000000000000000000000001".parse().unwrap();
+        let mut audio = Vec::new();
+        let written = twilio
+            .recording(&sid)
+            .download(RecordingFormat::Wav)
+            .requested_channels(2)
+            .write_to(&mut audio)
+            .await
+            .unwrap();
+        assert_eq!((written, audio.as_slice()), (4, &b"RIFF"[..]));
+        assert_eq!(
+            canned.seen(),
+            [
+                "GET https://api.twilio.com/2010-04-01/Accounts/AC1/Recordings/\
+                 RE00000000000000000000000000000001.wav?RequestedChannels=2",
+                "GET https://api.twilio.com/media/RE1.wav?Signature=abc",
+                "GET https://api.twilio.com/media/RE1.wav?Signature=abc",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn encodes_live_recording() {
+        let canned = Canned::new(
+            200,
+            r#"{"sid": "RE00000000000000000000000000000001",
+            "account_sid": "AC00000000000000000000000000000001",
+            "call_sid": "CA00000000000000000000000000000001", "channels": 2,
+            "conference_sid": null, "price": null, "price_unit": null, "duration": "-1",
+            "encryption_details": null, "error_code": null, "uri": "/uri", "status": "paused"}"#,
+        );
+        let twilio = canned.client();
+        let call_sid = "CA00000000000000000000000000000001".parse().unwrap();
+        let conf_sid = "CF00000000000000000000000000000001".parse().unwrap();
+        let sid = "RE00000000000000000000000000000001".parse().unwrap();
+
+        let call = twilio.call_sid(&call_sid);
+        call.start_recording()
+            .channels(RecordingChannels::Dual)
+            .trim(true)
+            .run()
+            .await
+            .unwrap();
+        call.start_recording()
+            .channels(RecordingChannels::Mono)
+            .trim(false)
+            .run()
+            .await
+            .unwrap();
+        let recording = call.recording(&sid);
+        recording.pause(PauseBehavior::Skip).await.unwrap();
+        recording.pause(PauseBehavior::Silence).await.unwrap();
+        recording.resume().await.unwrap();
+        twilio
+            .conference(&conf_sid)
+            .recording(&sid)
+            .stop()
+            .await
+            .unwrap();
+
+        let base = "POST https://api.twilio.com/2010-04-01/Accounts/AC1";
+        let call_url = format!("{}/Calls/{}/Recordings", base, call_sid);
+        let recording_url = format!("{}/{}.json", call_url, sid);
+        assert_eq!(
+            canned.seen(),
+            [
+                format!("{}.json", call_url),
+                format!("{}.json", call_url),
+                recording_url.clone(),
+                recording_url.clone(),
+                recording_url,
+                format!("{}/Conferences/{}/Recordings/{}.json", base, conf_sid, sid),
+            ]
+        );
+        assert_eq!(
+            canned.bodies(),
+            [
+                "RecordingChannels=dual&Trim=trim-silence",
+                "RecordingChannels=mono&Trim=do-not-trim",
+                "Status=paused&PauseBehavior=skip",
+                "Status=paused&PauseBehavior=silence",
+                "Status=in-progress",
+                "Status=stopped",
+            ]
+        );
+    }
+
+    #[test]
+    fn encodes_list_filters() {
+        let client = Twilio::new("AC123", "token").unwrap();
+        let call_sid = "CA00000000000000000000000000000001".parse().unwrap();
+        let conf_sid = "CF00000000000000000000000000000001".parse().unwrap();
+        let recordings = client
+            .recordings()
+            .for_call(&call_sid)
+            .for_conference(&conf_sid)
+            .date_created(TimeFilter::between("2020-01-01", "2020-01-31"));
+        assert_eq!(
+            recordings.list_url(),
+            "Recordings.json?CallSid=CA00000000000000000000000000000001\
+             &ConferenceSid=CF00000000000000000000000000000001\
+             &DateCreated%3E=2020-01-01&DateCreated%3C=2020-01-31"
+        );
+    }
+}
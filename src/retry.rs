@@ -0,0 +1,280 @@
+use super::{RateLimiter, Twilio, TwilioErr, TwilioResult};
+use hyper::{header::RETRY_AFTER, Body, Request, Response, StatusCode};
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    io,
+    time::{Duration, SystemTime},
+};
+
+/// How `run()` retries 429 `Too Many Requests`, 5xx responses and network
+/// failures. `POST`s that create something (`SendMsg`, `SendCall`) only
+/// retry when the connection could not be established, Twilio never saw
+/// those so they can't be sent twice, unless they were given an
+/// `idempotency_token`.
+///
+/// The default policy never retries.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+    respect_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::none()
+    }
+}
+
+impl RetryPolicy {
+    /// Only ever try once
+    pub fn none() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 1,
+            ..RetryPolicy::exponential(1)
+        }
+    }
+
+    /// Up to `max_attempts` tries in total, starting with a 500ms delay that
+    /// doubles every attempt (capped at 30s), with jitter and honouring
+    /// `Retry-After`
+    pub fn exponential(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: max_attempts.max(1),
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+            respect_retry_after: true,
+        }
+    }
+
+    /// total number of tries, including the first one
+    pub fn max_attempts(mut self, max_attempts: u32) -> RetryPolicy {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn base_delay(mut self, delay: Duration) -> RetryPolicy {
+        self.base_delay = delay;
+        self
+    }
+
+    pub fn max_delay(mut self, delay: Duration) -> RetryPolicy {
+        self.max_delay = delay;
+        self
+    }
+
+    /// randomize each delay between half and all of its value
+    pub fn jitter(mut self, jitter: bool) -> RetryPolicy {
+        self.jitter = jitter;
+        self
+    }
+
+    /// wait as long as the `Retry-After` header asks, in seconds or until an
+    /// HTTP date, up to `max_delay`, instead of the backoff
+    pub fn respect_retry_after(mut self, respect: bool) -> RetryPolicy {
+        self.respect_retry_after = respect;
+        self
+    }
+
+    fn retry_status(&self, status: StatusCode, idempotent: bool) -> bool {
+        idempotent && (status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error())
+    }
+
+    fn retry_err(&self, err: &TwilioErr, idempotent: bool) -> bool {
+        match *err {
+            TwilioErr::NetworkErr(ref e) => idempotent || e.is_connect(),
+            TwilioErr::Io(ref e) => idempotent || e.kind() == io::ErrorKind::ConnectionRefused,
+            _ => false,
+        }
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exp = 2u32.saturating_pow(attempt.saturating_sub(1));
+        let delay = self.base_delay.saturating_mul(exp).min(self.max_delay);
+        if !self.jitter {
+            return delay;
+        }
+        let half = delay / 2;
+        half + half.mul_f64(random())
+    }
+
+    fn delay(&self, attempt: u32, res: Option<&Response<Body>>) -> Duration {
+        let retry_after = res
+            .filter(|_| self.respect_retry_after)
+            .and_then(|res| res.headers().get(RETRY_AFTER))
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| retry_after(v.trim()))
+            .map(|delay| delay.min(self.max_delay));
+        retry_after.unwrap_or_else(|| self.backoff(attempt))
+    }
+}
+
+/// `Retry-After` is either a number of seconds or an HTTP date
+fn retry_after(value: &str) -> Option<Duration> {
+    match value.parse::<u64>() {
+        Ok(secs) => Some(Duration::from_secs(secs)),
+        Err(_) => {
+            let at = httpdate::parse_http_date(value).ok()?;
+            // a date in the past means retry now
+            Some(at.duration_since(SystemTime::now()).unwrap_or_default())
+        }
+    }
+}
+
+/// number in `[0, 1)`, good enough for jitter without pulling in `rand`
+fn random() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(0);
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Send the request built by `req` through the client's transport, rebuilding
+/// and resending it as the client's [`RetryPolicy`] allows. Every attempt
+/// waits for `limit`'s sender to have a free slot first.
+pub(crate) async fn send<F>(
+    client: &Twilio,
+    idempotent: bool,
+    limit: Option<(&RateLimiter, &str)>,
+    req: F,
+) -> TwilioResult<Response<Body>>
+where
+    F: Fn() -> TwilioResult<Request<Body>>,
+{
+    let policy = &client.retry;
+    let mut attempt = 1;
+    loop {
+        let last = attempt >= policy.max_attempts;
+        if let Some((limiter, sender)) = limit {
+            limiter.acquire(sender).await;
+        }
+        let delay = match client.transport.send(req()?).await {
+            Ok(res) if !last && policy.retry_status(res.status(), idempotent) => {
+                policy.delay(attempt, Some(&res))
+            }
+            Err(ref e) if !last && policy.retry_err(e, idempotent) => policy.delay(attempt, None),
+            res => return res,
+        };
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Transport, TwilioRequest};
+    use async_trait::async_trait;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[derive(Debug, Default)]
+    struct Flaky {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Transport for Flaky {
+        async fn send(&self, _req: Request<Body>) -> TwilioResult<Response<Body>> {
+            let res = match self.calls.fetch_add(1, Ordering::SeqCst) {
+                0 => Response::builder().status(503).body(Body::empty()),
+                1 => Response::builder()
+                    .status(429)
+                    .header(RETRY_AFTER, "0")
+                    .body(Body::empty()),
+                _ => Response::builder().body(Body::from(
+                    r#"{"recordings": [], "end": 0, "account_sid": "AC00000000000000000000000000000001", "start": 0,
+                    "page": 0, "page_size": 50}"#,
+                )),
+            };
+            Ok(res.unwrap())
+        }
+    }
+
+    fn twilio(flaky: &Arc<Flaky>, attempts: u32) -> Twilio {
+        Twilio::builder("AC1", "token")
+            .transport(flaky.clone())
+            .retry_policy(RetryPolicy::exponential(attempts).base_delay(Duration::from_millis(1)))
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn retries_get() {
+        let flaky = Arc::new(Flaky::default());
+        twilio(&flaky, 3).recordings().run().await.unwrap();
+        assert_eq!(flaky.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up() {
+        let flaky = Arc::new(Flaky::default());
+        let err = twilio(&flaky, 2).recordings().run().await.unwrap_err();
+        assert!(matches!(err, TwilioErr::Api(ref e) if e.status == 429));
+        assert_eq!(flaky.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn post_not_retried() {
+        let flaky = Arc::new(Flaky::default());
+        let twilio = twilio(&flaky, 3);
+        assert!(twilio.send_msg("from", "to", "body").run().await.is_err());
+        assert_eq!(flaky.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn token_retries_post() {
+        let flaky = Arc::new(Flaky::default());
+        // the final canned body is a recording list, only the attempts matter
+        let _ = twilio(&flaky, 3)
+            .send_msg("from", "to", "body")
+            .idempotency_token("key")
+            .run()
+            .await;
+        assert_eq!(flaky.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retries_wait_for_rate_limiter() {
+        let flaky = Arc::new(Flaky::default());
+        let limiter = crate::RateLimiter::new();
+        let start = tokio::time::Instant::now();
+        let _ = twilio(&flaky, 3)
+            .send_msg("+15005550006", "to", "body")
+            .idempotency_token("key")
+            .rate_limiter(&limiter)
+            .run()
+            .await;
+        assert_eq!(flaky.calls.load(Ordering::SeqCst), 3);
+        // long codes get one message a second, each retry waits for its own
+        assert!(start.elapsed() >= Duration::from_secs(2));
+    }
+
+    #[test]
+    fn caps_retry_after() {
+        let res = Response::builder()
+            .status(429)
+            .header(RETRY_AFTER, "3600")
+            .body(Body::empty())
+            .unwrap();
+        let policy = RetryPolicy::exponential(3);
+        assert_eq!(policy.delay(1, Some(&res)), Duration::from_secs(30));
+        let policy = policy.max_delay(Duration::from_secs(1));
+        assert_eq!(policy.delay(1, Some(&res)), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn parses_retry_after_date() {
+        let past = "Tue, 31 Aug 2010 20:36:28 GMT";
+        assert_eq!(retry_after(past), Some(Duration::ZERO));
+        let soon = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(120));
+        let delay = retry_after(&soon).unwrap();
+        assert!(delay > Duration::from_secs(100) && delay <= Duration::from_secs(120));
+        assert_eq!(retry_after("soon"), None);
+    }
+}
@@ -0,0 +1,322 @@
+use super::{validate_signature, webhook, TwilioResult};
+use hyper::{
+    body::HttpBody,
+    header::{HeaderValue, CONTENT_LENGTH, CONTENT_TYPE, HOST},
+    http::request::Parts,
+    service::Service,
+    Body, Method, Request, Response, StatusCode,
+};
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    fmt,
+    future::{self, Future},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use twiml::{Twiml, TwimlResult};
+use url::form_urlencoded;
+
+type BoxFuture = Pin<Box<dyn Future<Output = Response<Body>> + Send>>;
+type Handler = dyn Fn(&[u8]) -> BoxFuture + Send + Sync;
+
+/// Twilio's own payloads are a few KB at most
+const MAX_BODY: usize = 64 * 1024;
+
+/// Anything a webhook handler can answer with
+pub trait TwimlReply {
+    fn into_twiml(self) -> TwimlResult<String>;
+}
+
+impl<'a> TwimlReply for twiml::Response<'a> {
+    fn into_twiml(self) -> TwimlResult<String> {
+        self.build()
+    }
+}
+
+/// TwiML that was already built
+impl TwimlReply for String {
+    fn into_twiml(self) -> TwimlResult<String> {
+        Ok(self)
+    }
+}
+
+/// the result of `Response::build()`, for replies borrowing from the handler
+impl TwimlReply for TwimlResult<String> {
+    fn into_twiml(self) -> TwimlResult<String> {
+        self
+    }
+}
+
+/// A hyper `Service` for Twilio webhooks. Each request is checked against its
+/// `X-Twilio-Signature`, parsed into the payload type of the handler
+/// registered for its path and answered with the handler's TwiML.
+///
+/// ```rust,no_run
+/// use hyper::{service::make_service_fn, Server};
+/// use std::convert::Infallible;
+/// use twilio_async::{
+///     server::Webhooks,
+///     twiml::{Response, Twiml},
+///     webhook::IncomingMessage,
+/// };
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let hooks = Webhooks::new(std::env::var("TWILIO_TOKEN")?)
+///         .public_url("https://example.com")
+///         .route("/sms", |msg: IncomingMessage| async move {
+///             let reply = format!("Hello {}", msg.from);
+///             Response::new().msg(reply.as_str()).build()
+///         });
+///
+///     Server::bind(&([0, 0, 0, 0], 8080).into())
+///         .serve(make_service_fn(move |_| {
+///             let hooks = hooks.clone();
+///             async move { Ok::<_, Infallible>(hooks) }
+///         }))
+///         .await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Webhooks {
+    auth_token: Arc<str>,
+    public_url: Option<Arc<str>>,
+    validate: bool,
+    max_body: usize,
+    routes: HashMap<String, Arc<Handler>>,
+}
+
+impl fmt::Debug for Webhooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Webhooks")
+            .field("public_url", &self.public_url)
+            .field("validate", &self.validate)
+            .field("max_body", &self.max_body)
+            .field("routes", &self.routes.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl Webhooks {
+    pub fn new<S: AsRef<str>>(auth_token: S) -> Webhooks {
+        Webhooks {
+            auth_token: auth_token.as_ref().into(),
+            public_url: None,
+            validate: true,
+            max_body: MAX_BODY,
+            routes: HashMap::new(),
+        }
+    }
+
+    /// Scheme and host Twilio reaches this server at, e.g.
+    /// `https://example.com`. Signatures are computed over the url Twilio
+    /// requested, so set this when running behind a proxy or load balancer.
+    /// Defaults to `https://` + the `Host` header.
+    pub fn public_url<S: AsRef<str>>(mut self, url: S) -> Webhooks {
+        self.public_url = Some(url.as_ref().trim_end_matches('/').into());
+        self
+    }
+
+    /// Accept requests without checking `X-Twilio-Signature`, only meant for
+    /// local development
+    pub fn skip_validation(mut self) -> Webhooks {
+        self.validate = false;
+        self
+    }
+
+    /// Largest request body read before the signature is checked, bigger
+    /// requests are answered with `413 Payload Too Large`. Defaults to 64KB.
+    pub fn max_body(mut self, bytes: usize) -> Webhooks {
+        self.max_body = bytes;
+        self
+    }
+
+    /// Answer requests to `path` with `handler`, the request body is parsed
+    /// into the handler's argument, see the [`webhook`] module for payloads
+    pub fn route<P, H, F, R>(mut self, path: &str, handler: H) -> Webhooks
+    where
+        P: for<'de> Deserialize<'de> + Send + 'static,
+        H: Fn(P) -> F + Send + Sync + 'static,
+        F: Future<Output = R> + Send + 'static,
+        R: TwimlReply,
+    {
+        let handler = move |form: &[u8]| -> BoxFuture {
+            match webhook::from_form::<P>(form) {
+                Ok(payload) => {
+                    let reply = handler(payload);
+                    Box::pin(async move { twiml_response(reply.await.into_twiml()) })
+                }
+                Err(_) => Box::pin(future::ready(status(StatusCode::BAD_REQUEST))),
+            }
+        };
+        self.routes.insert(path.to_owned(), Arc::new(handler));
+        self
+    }
+
+    fn url(&self, req: &Parts) -> Option<String> {
+        let base = match self.public_url {
+            Some(ref url) => url.to_string(),
+            None => format!("https://{}", req.headers.get(HOST)?.to_str().ok()?),
+        };
+        let path = req.uri.path_and_query().map_or("/", |p| p.as_str());
+        Some(format!("{}{}", base, path))
+    }
+
+    fn verify(&self, req: &Parts, params: &[(String, String)]) -> bool {
+        let signature = req
+            .headers
+            .get("X-Twilio-Signature")
+            .and_then(|v| v.to_str().ok());
+        match (signature, self.url(req)) {
+            (Some(signature), Some(url)) => {
+                validate_signature(&self.auth_token, &url, params, signature)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn status(status: StatusCode) -> Response<Body> {
+    let mut res = Response::new(Body::empty());
+    *res.status_mut() = status;
+    res
+}
+
+fn twiml_response(twiml: TwimlResult<String>) -> Response<Body> {
+    match twiml {
+        Ok(twiml) => {
+            let mut res = Response::new(Body::from(twiml));
+            res.headers_mut()
+                .insert(CONTENT_TYPE, HeaderValue::from_static("text/xml"));
+            res
+        }
+        Err(_) => status(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+impl Service<Request<Body>> for Webhooks {
+    type Response = Response<Body>;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Response<Body>, Infallible>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let hooks = self.clone();
+        Box::pin(async move {
+            Ok(hooks
+                .dispatch(req)
+                .await
+                .unwrap_or_else(|_| status(StatusCode::BAD_REQUEST)))
+        })
+    }
+}
+
+impl Webhooks {
+    async fn dispatch(&self, req: Request<Body>) -> TwilioResult<Response<Body>> {
+        let handler = match self.routes.get(req.uri().path()) {
+            Some(handler) => handler.clone(),
+            None => return Ok(status(StatusCode::NOT_FOUND)),
+        };
+        let (parts, mut body) = req.into_parts();
+        // GET webhooks carry their parameters in the query string, which is
+        // already part of the signed url
+        let (form, params) = if parts.method == Method::GET {
+            let query = parts.uri.query().unwrap_or_default();
+            (query.as_bytes().to_vec(), Vec::new())
+        } else {
+            // nothing is authenticated yet, don't buffer more than a webhook
+            // could ever need
+            let declared = parts
+                .headers
+                .get(CONTENT_LENGTH)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok());
+            if declared.is_some_and(|len| len > self.max_body as u64) {
+                return Ok(status(StatusCode::PAYLOAD_TOO_LARGE));
+            }
+            let mut buf = Vec::new();
+            while let Some(chunk) = body.data().await {
+                let chunk = chunk?;
+                if buf.len() + chunk.len() > self.max_body {
+                    return Ok(status(StatusCode::PAYLOAD_TOO_LARGE));
+                }
+                buf.extend_from_slice(&chunk);
+            }
+            let body = buf;
+            let params = form_urlencoded::parse(&body).into_owned().collect();
+            (body, params)
+        };
+        if self.validate && !self.verify(&parts, &params) {
+            return Ok(status(StatusCode::FORBIDDEN));
+        }
+        Ok(handler(&form).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{compute_signature, webhook::IncomingCall};
+
+    const BODY: &str = "CallSid=CA1&AccountSid=AC1&From=%2B14155550100&To=%2B14155550101\
+        &CallStatus=ringing&Direction=inbound";
+
+    fn request(signature: &str) -> Request<Body> {
+        Request::post("/voice")
+            .header(HOST, "example.com")
+            .header("X-Twilio-Signature", signature)
+            .body(Body::from(BODY))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn signed_request() {
+        let mut hooks = Webhooks::new("token").route("/voice", |call: IncomingCall| async move {
+            assert_eq!(call.from, "+14155550100");
+            twiml::Response::new().say("hi").hangup()
+        });
+        let params = form_urlencoded::parse(BODY.as_bytes())
+            .into_owned()
+            .collect::<Vec<_>>();
+        let signature = compute_signature("token", "https://example.com/voice", &params);
+
+        let res = hooks.call(request(&signature)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()[CONTENT_TYPE], "text/xml");
+        let body = hyper::body::to_bytes(res.into_body()).await.unwrap();
+        assert!(body.starts_with(b"<Response><Say"));
+
+        let res = hooks.call(request("forged")).await.unwrap();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn rejects_oversized_body() {
+        let mut hooks = Webhooks::new("token")
+            .max_body(16)
+            .route("/voice", |_: IncomingCall| async move {
+                twiml::Response::new().hangup()
+            });
+        // the declared length is checked before reading anything
+        let req = Request::post("/voice")
+            .header(CONTENT_LENGTH, 1 << 30)
+            .body(Body::empty())
+            .unwrap();
+        let res = hooks.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        // and so is a chunked body that never declared one
+        let chunks = futures::stream::iter(vec![Ok::<_, std::io::Error>(BODY)]);
+        let req = Request::post("/voice")
+            .body(Body::wrap_stream(chunks))
+            .unwrap();
+        let res = hooks.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
@@ -0,0 +1,140 @@
+//! Typed resource SIDs.
+//!
+//! A SID is a two letter prefix naming the kind of resource followed by 32 hex
+//! characters, parsing checks both so a conference SID can't be handed to
+//! `Twilio::recording`.
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{error::Error, fmt, str::FromStr};
+
+/// Returned when a string is not a SID of the expected kind
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSidError {
+    /// prefixes the SID could have started with
+    pub expected: &'static [&'static str],
+    pub sid: String,
+}
+
+impl Error for ParseSidError {}
+
+impl fmt::Display for ParseSidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} is not a SID starting with {} followed by 32 hex characters",
+            self.sid,
+            self.expected.join(" or ")
+        )
+    }
+}
+
+fn validate(sid: &str, prefixes: &'static [&'static str]) -> Result<(), ParseSidError> {
+    let valid = sid.len() == 34
+        && prefixes.iter().any(|prefix| sid.starts_with(prefix))
+        && sid[2..].bytes().all(|b| b.is_ascii_hexdigit());
+    if valid {
+        Ok(())
+    } else {
+        Err(ParseSidError {
+            expected: prefixes,
+            sid: sid.to_owned(),
+        })
+    }
+}
+
+macro_rules! sid {
+    ($(#[$meta:meta])* $name:ident, [$($prefix:literal),+]) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct $name(String);
+
+        impl $name {
+            pub const PREFIXES: &'static [&'static str] = &[$($prefix),+];
+
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ParseSidError;
+
+            fn from_str(sid: &str) -> Result<Self, Self::Err> {
+                validate(sid, Self::PREFIXES)?;
+                Ok($name(sid.to_owned()))
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+                ser.serialize_str(&self.0)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+                String::deserialize(de)?.parse().map_err(de::Error::custom)
+            }
+        }
+    };
+}
+
+sid!(AccountSid, ["AC"]);
+sid!(
+    /// `SM` for SMS, `MM` for MMS
+    MessageSid,
+    ["SM", "MM"]
+);
+sid!(CallSid, ["CA"]);
+sid!(ConferenceSid, ["CF"]);
+sid!(RecordingSid, ["RE"]);
+sid!(TranscriptionSid, ["TR"]);
+sid!(MediaSid, ["ME"]);
+sid!(
+    /// an incoming phone number
+    PhoneNumberSid,
+    ["PN"]
+);
+sid!(MessagingServiceSid, ["MG"]);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_prefix_and_hex() {
+        let sid = "CF0123456789abcdef0123456789abcdef";
+        assert_eq!(sid.parse::<ConferenceSid>().unwrap().to_string(), sid);
+        assert!(sid.parse::<RecordingSid>().is_err());
+        assert!("MM0123456789abcdef0123456789abcdef"
+            .parse::<MessageSid>()
+            .is_ok());
+        assert!("CF0123456789abcdef0123456789abcdeg"
+            .parse::<ConferenceSid>()
+            .is_err());
+        assert!("CF0123".parse::<ConferenceSid>().is_err());
+    }
+
+    #[test]
+    fn converts_into_twilio_err() {
+        fn parse(sid: &str) -> crate::TwilioResult<CallSid> {
+            Ok(sid.parse::<CallSid>()?)
+        }
+        assert!(parse("CA0123456789abcdef0123456789abcdef").is_ok());
+        assert!(matches!(
+            parse("CF0123456789abcdef0123456789abcdef"),
+            Err(crate::TwilioErr::SidErr(_))
+        ));
+    }
+}
@@ -0,0 +1,150 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::borrow::Borrow;
+use url::{Position, Url};
+
+/// Base64 HMAC-SHA1 of `url` followed by every parameter name and value,
+/// sorted by name, keyed with the account's auth token
+pub fn compute_signature<I, K, V>(auth_token: &str, url: &str, params: I) -> String
+where
+    K: AsRef<str>,
+    V: AsRef<str>,
+    I: IntoIterator,
+    I::Item: Borrow<(K, V)>,
+{
+    let mut pairs = params
+        .into_iter()
+        .map(|pair| {
+            let (k, v) = pair.borrow();
+            (k.as_ref().to_owned(), v.as_ref().to_owned())
+        })
+        .collect::<Vec<_>>();
+    pairs.sort();
+
+    let mut mac =
+        Hmac::<Sha1>::new_from_slice(auth_token.as_bytes()).expect("hmac takes any key length");
+    mac.update(url.as_bytes());
+    for (k, v) in pairs {
+        mac.update(k.as_bytes());
+        mac.update(v.as_bytes());
+    }
+    STANDARD.encode(mac.finalize().into_bytes())
+}
+
+/// Check `signature` (the `X-Twilio-Signature` header) for a form encoded
+/// webhook. `url` is the full url Twilio requested, including the query
+/// string, and `params` the decoded POST body (empty for a GET).
+///
+/// Twilio may sign the url with or without the default port depending on how
+/// it was configured, both forms are accepted. See
+/// <https://www.twilio.com/docs/usage/security#validating-requests>
+pub fn validate_signature<I, K, V>(auth_token: &str, url: &str, params: I, signature: &str) -> bool
+where
+    K: AsRef<str>,
+    V: AsRef<str>,
+    I: IntoIterator,
+    I::Item: Borrow<(K, V)>,
+{
+    let pairs = params
+        .into_iter()
+        .map(|pair| {
+            let (k, v) = pair.borrow();
+            (k.as_ref().to_owned(), v.as_ref().to_owned())
+        })
+        .collect::<Vec<_>>();
+
+    url_variants(url).iter().any(|url| {
+        let expected = compute_signature(auth_token, url, &pairs);
+        constant_time_eq(expected.as_bytes(), signature.as_bytes())
+    })
+}
+
+/// Check `signature` for a webhook with a JSON (or any non form) body. Twilio
+/// signs the url alone and adds a `bodySHA256` query parameter holding the
+/// hex SHA-256 of `body`, which has to match as well.
+pub fn validate_body_signature(auth_token: &str, url: &str, body: &[u8], signature: &str) -> bool {
+    let parsed = match Url::parse(url) {
+        Ok(url) => url,
+        Err(_) => return false,
+    };
+    let hash = parsed
+        .query_pairs()
+        .find(|(k, _)| k == "bodySHA256")
+        .map(|(_, v)| v.into_owned());
+    match hash {
+        Some(hash) => {
+            let actual = hex(&Sha256::digest(body));
+            constant_time_eq(actual.as_bytes(), hash.to_ascii_lowercase().as_bytes())
+                && validate_signature(auth_token, url, &[] as &[(&str, &str)], signature)
+        }
+        None => false,
+    }
+}
+
+/// `url` as given, without its port and with the scheme's default port added
+fn url_variants(url: &str) -> Vec<String> {
+    let mut urls = vec![url.to_owned()];
+    if let Ok(parsed) = Url::parse(url) {
+        let host = &parsed[..Position::AfterHost];
+        let rest = &parsed[Position::AfterPort..];
+        urls.push(format!("{}{}", host, rest));
+        if let Some(port) = parsed.port_or_known_default() {
+            urls.push(format!("{}:{}{}", host, port, rest));
+        }
+    }
+    urls
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN: &str = "12345";
+    const URL: &str = "https://mycompany.com/myapp.php?foo=1&bar=2";
+    const PARAMS: [(&str, &str); 5] = [
+        ("CallSid", "CA1234567890ABCDE"),
+        ("Caller", "+14158675309"),
+        ("Digits", "1234"),
+        ("From", "+14158675309"),
+        ("To", "+18005551212"),
+    ];
+
+    #[test]
+    fn form_signature() {
+        let sig = "RSOYDt4T1cUTdK1PDd93/VVr8B8=";
+        assert_eq!(compute_signature(TOKEN, URL, &PARAMS), sig);
+        assert!(validate_signature(TOKEN, URL, &PARAMS, sig));
+        assert!(!validate_signature(TOKEN, URL, &PARAMS[1..], sig));
+        assert!(!validate_signature("54321", URL, &PARAMS, sig));
+    }
+
+    #[test]
+    fn port_stripping() {
+        let sig = "RSOYDt4T1cUTdK1PDd93/VVr8B8=";
+        let with_port = "https://mycompany.com:443/myapp.php?foo=1&bar=2";
+        assert!(validate_signature(TOKEN, with_port, &PARAMS, sig));
+
+        let sig = compute_signature(TOKEN, with_port, &PARAMS);
+        assert!(validate_signature(TOKEN, URL, &PARAMS, &sig));
+    }
+
+    #[test]
+    fn body_signature() {
+        let body = br#"{"property": "value", "boolean": true}"#;
+        let url = "https://mycompany.com/myapp.php?foo=1&bar=2&bodySHA256=0a1ff7634d9ab3b95db5c9a2dfe9416e41502b283a80c7cf19632632f96e6620";
+        let sig = "a9nBmqA0ju/hNViExpshrM61xv4=";
+        assert!(validate_body_signature(TOKEN, url, body, sig));
+        assert!(!validate_body_signature(TOKEN, url, b"{}", sig));
+        assert!(!validate_body_signature(TOKEN, URL, body, sig));
+    }
+}
@@ -0,0 +1,150 @@
+use super::{
+    AccountSid, DateTime, Execute, GetRecording, Page, Paging, RecordingSid, TranscriptionSid,
+    Twilio, TwilioErr, TwilioRequest, TwilioResp,
+};
+#[cfg(feature = "rust_decimal")]
+use crate::Price;
+use async_trait::async_trait;
+use hyper::{self, Method};
+use serde::Deserialize;
+
+impl<'a> GetRecording<'a> {
+    /// Transcriptions made from this recording
+    pub fn transcriptions(&self) -> Transcriptions<'a> {
+        Transcriptions {
+            recording_sid: Some(self.recording.sid()),
+            client: self.client,
+            paging: Paging::default(),
+        }
+    }
+}
+
+// GET ONE TRANSCRIPTION
+#[derive(Debug)]
+pub struct GetTranscription<'a> {
+    pub transcription_sid: &'a TranscriptionSid,
+    pub client: &'a Twilio,
+}
+
+execute!(GetTranscription);
+
+#[async_trait]
+impl<'a> TwilioRequest for GetTranscription<'a> {
+    type Resp = TranscriptionResp;
+
+    async fn run(&self) -> TwilioResp<Self::Resp> {
+        let url = format!("Transcriptions/{}.json", self.transcription_sid);
+        self.execute(Method::GET, url, None).await
+    }
+}
+
+impl<'a> GetTranscription<'a> {
+    pub async fn delete(&self) -> TwilioResp<()> {
+        let url = format!("Transcriptions/{}.json", self.transcription_sid);
+        self.execute(Method::DELETE, url, None).await
+    }
+}
+
+// GET ALL TRANSCRIPTIONS
+#[derive(Debug)]
+pub struct Transcriptions<'a> {
+    /// only the transcriptions of this recording when set
+    pub recording_sid: Option<&'a RecordingSid>,
+    pub client: &'a Twilio,
+    pub paging: Paging,
+}
+
+impl<'a> Transcriptions<'a> {
+    fn list_url(&self) -> String {
+        match self.recording_sid {
+            Some(sid) => format!("Recordings/{}/Transcriptions.json", sid),
+            None => "Transcriptions.json".into(),
+        }
+    }
+}
+
+execute!(Transcriptions);
+paginate!(Transcriptions, ListTranscriptionsResp);
+
+#[async_trait]
+impl<'a> TwilioRequest for Transcriptions<'a> {
+    type Resp = ListTranscriptionsResp;
+
+    async fn run(&self) -> TwilioResp<Self::Resp> {
+        let url = self.paging.url(self.list_url());
+        self.execute(Method::GET, url, None).await
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ListTranscriptionsResp {
+    pub transcriptions: Vec<TranscriptionResp>,
+    pub page: usize,
+    pub page_size: usize,
+    pub start: usize,
+    pub end: usize,
+    pub uri: String,
+    pub next_page_uri: Option<String>,
+    pub previous_page_uri: Option<String>,
+}
+
+impl Page for ListTranscriptionsResp {
+    type Item = TranscriptionResp;
+
+    fn next_page_uri(&self) -> Option<&str> {
+        self.next_page_uri.as_deref()
+    }
+
+    fn into_items(self) -> Vec<TranscriptionResp> {
+        self.transcriptions
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct TranscriptionResp {
+    pub sid: TranscriptionSid,
+    pub account_sid: AccountSid,
+    pub recording_sid: RecordingSid,
+    pub status: TranscriptionStatus,
+    pub transcription_text: Option<String>,
+    /// seconds of audio transcribed
+    pub duration: Option<String>,
+    #[cfg(not(feature = "rust_decimal"))]
+    pub price: Option<String>,
+    #[cfg(not(feature = "rust_decimal"))]
+    pub price_unit: Option<String>,
+    #[cfg(feature = "rust_decimal")]
+    #[serde(flatten, deserialize_with = "crate::price::deserialize")]
+    pub price: Option<Price>,
+    #[serde(default, deserialize_with = "crate::datetime::deserialize_opt")]
+    pub date_created: Option<DateTime>,
+    #[serde(default, deserialize_with = "crate::datetime::deserialize_opt")]
+    pub date_updated: Option<DateTime>,
+    pub api_version: Option<String>,
+    pub uri: String,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub enum TranscriptionStatus {
+    #[serde(rename = "in-progress")]
+    inprogress,
+    completed,
+    failed,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Twilio;
+
+    #[test]
+    fn lists_account_or_recording() {
+        let client = Twilio::new("AC123", "token").unwrap();
+        assert_eq!(client.transcriptions().list_url(), "Transcriptions.json");
+        let sid = "RE00000000000000000000000000000001".parse().unwrap();
+        assert_eq!(
+            client.recording(&sid).transcriptions().list_url(),
+            "Recordings/RE00000000000000000000000000000001/Transcriptions.json"
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use super::{TwilioErr, TwilioResult};
+use async_trait::async_trait;
+use hyper::{
+    client::{connect::Connect, HttpConnector},
+    Body, Client, Request, Response,
+};
+use hyper_tls::HttpsConnector;
+use std::{fmt, sync::Arc};
+
+/// The HTTP backend requests are sent through. `Twilio` uses
+/// [`HyperTransport`] unless another one is given to
+/// [`TwilioBuilder::transport`](crate::TwilioBuilder::transport), so a
+/// rustls, reqwest or in-memory backend only has to implement `send`.
+#[async_trait]
+pub trait Transport: fmt::Debug + Send + Sync {
+    async fn send(&self, req: Request<Body>) -> TwilioResult<Response<Body>>;
+}
+
+#[async_trait]
+impl<T: Transport + ?Sized> Transport for Arc<T> {
+    async fn send(&self, req: Request<Body>) -> TwilioResult<Response<Body>> {
+        (**self).send(req).await
+    }
+}
+
+/// hyper backed transport, uses `hyper-tls` unless constructed with
+/// another connector
+#[derive(Debug, Clone)]
+pub struct HyperTransport<C = HttpsConnector<HttpConnector>> {
+    client: Client<C, Body>,
+}
+
+impl HyperTransport {
+    pub fn new() -> HyperTransport {
+        HyperTransport::with_connector(HttpsConnector::new())
+    }
+}
+
+impl Default for HyperTransport {
+    fn default() -> Self {
+        HyperTransport::new()
+    }
+}
+
+impl<C> HyperTransport<C>
+where
+    C: Connect + Clone + Send + Sync + 'static,
+{
+    pub fn with_connector(connector: C) -> HyperTransport<C> {
+        HyperTransport {
+            client: Client::builder().build::<_, Body>(connector),
+        }
+    }
+
+    pub fn with_client(client: Client<C, Body>) -> HyperTransport<C> {
+        HyperTransport { client }
+    }
+}
+
+#[async_trait]
+impl<C> Transport for HyperTransport<C>
+where
+    C: Connect + Clone + fmt::Debug + Send + Sync + 'static,
+{
+    async fn send(&self, req: Request<Body>) -> TwilioResult<Response<Body>> {
+        self.client
+            .request(req)
+            .await
+            .map_err(TwilioErr::NetworkErr)
+    }
+}
+
+/// In-memory transport for tests, answers from a script and records every
+/// request it was sent
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub(crate) struct Canned {
+    /// answered in order, then `status` and `body` for everything after
+    replies: std::sync::Mutex<std::collections::VecDeque<Response<Body>>>,
+    status: u16,
+    body: &'static str,
+    /// `METHOD uri` of each request
+    pub(crate) seen: std::sync::Mutex<Vec<String>>,
+    /// form body of each request, empty for GET & DELETE
+    pub(crate) bodies: std::sync::Mutex<Vec<String>>,
+}
+
+#[cfg(test)]
+impl Canned {
+    pub(crate) fn new(status: u16, body: &'static str) -> Arc<Canned> {
+        Arc::new(Canned {
+            status,
+            body,
+            ..Canned::default()
+        })
+    }
+
+    pub(crate) fn scripted(replies: Vec<Response<Body>>, body: &'static str) -> Arc<Canned> {
+        Arc::new(Canned {
+            replies: std::sync::Mutex::new(replies.into()),
+            status: 200,
+            body,
+            ..Canned::default()
+        })
+    }
+
+    /// client for account `AC1` sending through this transport
+    pub(crate) fn client(self: &Arc<Self>) -> crate::Twilio {
+        crate::Twilio::builder("AC1", "token")
+            .transport(self.clone())
+            .build()
+            .unwrap()
+    }
+
+    pub(crate) fn seen(&self) -> Vec<String> {
+        self.seen.lock().unwrap().clone()
+    }
+
+    pub(crate) fn bodies(&self) -> Vec<String> {
+        self.bodies.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl Transport for Canned {
+    async fn send(&self, req: Request<Body>) -> TwilioResult<Response<Body>> {
+        self.seen
+            .lock()
+            .unwrap()
+            .push(format!("{} {}", req.method(), req.uri()));
+        let body = hyper::body::to_bytes(req.into_body()).await?;
+        self.bodies
+            .lock()
+            .unwrap()
+            .push(String::from_utf8_lossy(&body).into_owned());
+        let scripted = self.replies.lock().unwrap().pop_front();
+        Ok(scripted.unwrap_or_else(|| {
+            Response::builder()
+                .status(self.status)
+                .body(Body::from(self.body))
+                .unwrap()
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TwilioRequest;
+
+    #[tokio::test]
+    async fn canned_response() {
+        let canned = Canned::new(
+            200,
+            r#"{"account_sid": "AC00000000000000000000000000000001", "date_created": null,
+                "date_updated": "Tue, 31 Aug 2010 20:36:28 +0000", "friendly_name": "room", "region": "us1",
+                "sid": "CF00000000000000000000000000000001", "status": "completed", "uri": "/uri"}"#,
+        );
+        let twilio = canned.client();
+
+        let sid = "CF00000000000000000000000000000001".parse().unwrap();
+        let conf = twilio.conference(&sid).run().await.unwrap();
+        assert_eq!(conf.friendly_name, "room");
+        assert_eq!(
+            canned.seen(),
+            [
+                "GET https://api.twilio.com/2010-04-01/Accounts/AC1/Conferences/\
+              CF00000000000000000000000000000001.json"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn api_error() {
+        let canned = Canned::new(
+            400,
+            r#"{"code": 21211, "message": "The 'To' number is not valid.",
+                "more_info": "https://www.twilio.com/docs/errors/21211", "status": 400}"#,
+        );
+        let twilio = canned.client();
+
+        match twilio.send_msg("+15005550006", "nope", "hi").run().await {
+            Err(crate::TwilioErr::Api(e)) => {
+                assert!(e.is_invalid_to_number());
+                assert_eq!(e.status, 400);
+            }
+            other => panic!("expected an api error, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use super::{
+    CallStatus, ConferenceEvent, DateTime, Direction, MsgStatus, RecordingStatus, TwilioResult,
+};
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
+
+/// Parse the form encoded body of a webhook request into one of the payload
+/// types below
+///
+/// ```rust
+/// use twilio_async::{
+///     twiml::{Response, Twiml},
+///     webhook::{self, IncomingMessage},
+/// };
+///
+/// fn handle(body: &[u8]) -> Result<String, Box<dyn std::error::Error>> {
+///     let msg: IncomingMessage = webhook::from_form(body)?;
+///     let reply = format!("got {} attachment(s) from {}", msg.media.len(), msg.from);
+///     Ok(Response::new().msg(reply.as_str()).build()?)
+/// }
+/// # handle(b"MessageSid=SM1&AccountSid=AC1&From=%2B15005550006&To=%2B15005550001&Body=hi&NumMedia=0").unwrap();
+/// ```
+pub fn from_form<T>(body: &[u8]) -> TwilioResult<T>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    Ok(serde_urlencoded::from_bytes(body)?)
+}
+
+/// Location Twilio attaches to the `From` and `To` numbers, when known
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Geo {
+    pub from_city: Option<String>,
+    pub from_state: Option<String>,
+    pub from_zip: Option<String>,
+    pub from_country: Option<String>,
+    pub to_city: Option<String>,
+    pub to_state: Option<String>,
+    pub to_zip: Option<String>,
+    pub to_country: Option<String>,
+}
+
+/// One of the `MediaUrl{N}` / `MediaContentType{N}` pairs of an MMS
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Media {
+    pub url: String,
+    pub content_type: Option<String>,
+}
+
+/// Body of the request Twilio sends to a number's messaging webhook
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct IncomingMessage {
+    pub message_sid: String,
+    pub account_sid: String,
+    pub messaging_service_sid: Option<String>,
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub body: String,
+    #[serde(default)]
+    pub num_media: usize,
+    pub num_segments: Option<usize>,
+    pub sms_status: Option<MsgStatus>,
+    pub api_version: Option<String>,
+    #[serde(flatten)]
+    pub geo: Geo,
+    #[serde(flatten, deserialize_with = "media")]
+    pub media: Vec<Media>,
+}
+
+/// Body of the request Twilio sends to a number's voice webhook, and to the
+/// `action` of a `<Gather>`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct IncomingCall {
+    pub call_sid: String,
+    pub account_sid: String,
+    pub from: String,
+    pub to: String,
+    pub call_status: CallStatus,
+    pub direction: Direction,
+    pub api_version: Option<String>,
+    pub forwarded_from: Option<String>,
+    pub caller_name: Option<String>,
+    pub parent_call_sid: Option<String>,
+    /// keys pressed during a `<Gather>`
+    pub digits: Option<String>,
+    /// transcribed speech from a `<Gather input="speech">`
+    pub speech_result: Option<String>,
+    /// between 0 and 1
+    pub confidence: Option<f32>,
+    #[serde(flatten)]
+    pub geo: Geo,
+}
+
+/// Sent to a message's `StatusCallback` every time its status changes
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct MessageStatusCallback {
+    pub message_sid: String,
+    pub account_sid: String,
+    pub messaging_service_sid: Option<String>,
+    pub from: Option<String>,
+    pub to: String,
+    pub message_status: MsgStatus,
+    /// set when the message failed or was undelivered
+    pub error_code: Option<usize>,
+    pub api_version: Option<String>,
+}
+
+/// Sent to a call's `StatusCallback` for each requested `CallbackEvent`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CallStatusCallback {
+    pub call_sid: String,
+    pub account_sid: String,
+    pub from: String,
+    pub to: String,
+    pub call_status: CallStatus,
+    pub direction: Direction,
+    pub parent_call_sid: Option<String>,
+    /// seconds, only once the call has completed
+    pub call_duration: Option<u64>,
+    /// order of the callbacks for this call, they may arrive out of order
+    pub sequence_number: Option<u32>,
+    /// time the event occurred
+    #[serde(default, deserialize_with = "crate::datetime::deserialize_opt")]
+    pub timestamp: Option<DateTime>,
+    pub callback_source: Option<String>,
+    pub sip_response_code: Option<u16>,
+    pub recording_sid: Option<String>,
+    pub recording_url: Option<String>,
+    pub recording_duration: Option<u64>,
+    pub api_version: Option<String>,
+}
+
+/// Sent to a recording's `RecordingStatusCallback`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RecordingStatusCallback {
+    pub account_sid: String,
+    pub call_sid: Option<String>,
+    pub conference_sid: Option<String>,
+    pub recording_sid: String,
+    pub recording_url: String,
+    pub recording_status: RecordingStatus,
+    /// seconds
+    pub recording_duration: Option<u64>,
+    pub recording_channels: Option<u8>,
+    #[serde(default, deserialize_with = "crate::datetime::deserialize_opt")]
+    pub recording_start_time: Option<DateTime>,
+    pub recording_source: Option<String>,
+    pub error_code: Option<usize>,
+}
+
+/// Sent to a conference's `StatusCallback` for each requested event
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ConferenceStatusCallback {
+    pub conference_sid: String,
+    pub account_sid: String,
+    pub friendly_name: String,
+    pub status_callback_event: ConferenceEvent,
+    /// time the event occurred
+    #[serde(default, deserialize_with = "crate::datetime::deserialize_opt")]
+    pub timestamp: Option<DateTime>,
+    pub sequence_number: Option<u32>,
+    /// the participant the event is about, for `participant-*` events
+    pub call_sid: Option<String>,
+    pub muted: Option<bool>,
+    pub hold: Option<bool>,
+    pub coaching: Option<bool>,
+    pub end_conference_on_exit: Option<bool>,
+    pub start_conference_on_enter: Option<bool>,
+    /// set on `conference-end`
+    pub reason_conference_ended: Option<String>,
+    pub call_sid_ending_conference: Option<String>,
+}
+
+// collect `MediaUrl0..N` with their content types, in order
+fn media<'de, D>(de: D) -> Result<Vec<Media>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut fields = HashMap::<String, String>::deserialize(de)?;
+    let mut media = Vec::new();
+    while let Some(url) = fields.remove(&format!("MediaUrl{}", media.len())) {
+        media.push(Media {
+            content_type: fields.remove(&format!("MediaContentType{}", media.len())),
+            url,
+        });
+    }
+    Ok(media)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn incoming_mms() {
+        let body = "MessageSid=MM1&AccountSid=AC1&From=%2B14155550100&To=%2B14155550101\
+            &Body=look&NumMedia=2&SmsStatus=received&FromCity=SAN+FRANCISCO&FromCountry=US\
+            &MediaUrl1=https%3A%2F%2Fapi.twilio.com%2F2&MediaContentType1=image%2Fpng\
+            &MediaUrl0=https%3A%2F%2Fapi.twilio.com%2F1&MediaContentType0=image%2Fjpeg";
+        let msg: IncomingMessage = from_form(body.as_bytes()).unwrap();
+        assert_eq!(msg.from, "+14155550100");
+        assert_eq!(msg.num_media, 2);
+        assert_eq!(msg.sms_status, Some(MsgStatus::received));
+        assert_eq!(msg.geo.from_city.as_deref(), Some("SAN FRANCISCO"));
+        let urls = msg.media.iter().map(|m| m.url.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            urls,
+            ["https://api.twilio.com/1", "https://api.twilio.com/2"]
+        );
+        assert_eq!(msg.media[1].content_type.as_deref(), Some("image/png"));
+    }
+
+    #[test]
+    fn incoming_gather() {
+        let body = "CallSid=CA1&AccountSid=AC1&From=%2B14155550100&To=%2B14155550101\
+            &CallStatus=in-progress&Direction=inbound&Digits=42\
+            &SpeechResult=talk+to+sales&Confidence=0.91";
+        let call: IncomingCall = from_form(body.as_bytes()).unwrap();
+        assert_eq!(call.call_status, CallStatus::inprogress);
+        assert_eq!(call.direction, Direction::inbound);
+        assert_eq!(call.digits.as_deref(), Some("42"));
+        assert_eq!(call.speech_result.as_deref(), Some("talk to sales"));
+        assert_eq!(call.confidence, Some(0.91));
+    }
+
+    #[test]
+    fn conference_callback() {
+        let body = "ConferenceSid=CF1&AccountSid=AC1&FriendlyName=bridge\
+            &StatusCallbackEvent=participant-join&CallSid=CA1&Muted=false&Hold=false\
+            &SequenceNumber=3&Timestamp=Tue%2C+31+Aug+2010+20%3A36%3A28+%2B0000";
+        let cb: ConferenceStatusCallback = from_form(body.as_bytes()).unwrap();
+        assert_eq!(cb.status_callback_event, ConferenceEvent::ParticipantJoin);
+        assert_eq!(cb.muted, Some(false));
+        assert_eq!(cb.sequence_number, Some(3));
+        assert_eq!(
+            cb.timestamp.unwrap().as_str(),
+            "Tue, 31 Aug 2010 20:36:28 +0000"
+        );
+    }
+}